@@ -0,0 +1,167 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use leptos::prelude::*;
+use wynnmap_types::{
+    attack::{AttackHop, AttackRoute, attack_route},
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    components::guildselect::GuildSelect, sectimer::SecondTimer, settings::use_toggle,
+    util::fmt_time_short,
+};
+
+/// Best attack route from the claim of the picked guild to the target territory
+#[derive(Clone, Copy)]
+pub struct AttackPlanner {
+    /// Tag of the attacking guild
+    pub guild: RwSignal<Option<Arc<str>>>,
+    /// Name of the territory to attack
+    pub target: RwSignal<Option<Arc<str>>>,
+    pub route: Memo<Option<AttackRoute>>,
+}
+
+impl AttackPlanner {
+    pub fn new(
+        terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+        state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    ) -> Self {
+        let SecondTimer(now) = expect_context::<SecondTimer>();
+        let show = use_attack_route_toggle();
+        let guild = RwSignal::new(None::<Arc<str>>);
+        let target = RwSignal::new(None::<Arc<str>>);
+
+        let route = Memo::new(move |_| {
+            if !show.get() {
+                return None;
+            }
+
+            attack_route(
+                &terrs.read(),
+                &state.read(),
+                &guild.get()?,
+                &target.get()?,
+                now.get(),
+            )
+        });
+
+        Self {
+            guild,
+            target,
+            route,
+        }
+    }
+
+    /// Names of the territories on the route starting from the territory of the attacking guild
+    pub fn path(self) -> Signal<Vec<Arc<str>>> {
+        Signal::derive(move || {
+            self.route
+                .read()
+                .as_ref()
+                .map(|r| {
+                    std::iter::once(r.start.clone())
+                        .chain(r.hops.iter().map(|h| h.name.clone()))
+                        .collect()
+                })
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn use_attack_route_toggle() -> RwSignal<bool> {
+    use_toggle("attackroute", false)
+}
+
+/// Sidebar panel for finding the best route for attacking a territory
+#[component]
+pub fn AttackRoutePanel(
+    #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    planner: AttackPlanner,
+    /// The territory selected on the map, used as the target and set when a hop in the panel is clicked
+    selected: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let show = use_attack_route_toggle();
+
+    let hop = move |(i, h): (usize, AttackHop)| {
+        let name = h.name.clone();
+        let cooldown =
+            (!h.cooldown.is_zero()).then(|| format!(" · cooldown {}", fmt_time_short(h.cooldown)));
+
+        view! {
+            <button
+                class="flex flex-col text-left px-1 hover:bg-neutral-700"
+                on:click=move |_| selected.set(Some(name.clone()))
+            >
+                <span class="truncate">{format!("{}. {} [{}]", i + 1, h.name, h.owner.prefix)}</span>
+                <span class="text-sm text-neutral-400">
+                    {if h.enemy {
+                        view! { <span style:color=h.tier.color()>{h.tier.to_string()}</span> }.into_any()
+                    } else {
+                        view! { <span>"Unclaimed"</span> }.into_any()
+                    }}
+                    {cooldown}
+                </span>
+            </button>
+        }
+    };
+
+    let details = move || {
+        let guild = planner.guild.read().is_some();
+        let target = planner.target.read().is_some();
+
+        match planner.route.get() {
+            Some(route) => view! {
+                <p class="text-base">
+                    {format!(
+                        "From {} · {} hops · {} enemy · score {}",
+                        route.start,
+                        route.hops.len(),
+                        route.enemies(),
+                        route.score
+                    )}
+                </p>
+                <div class="flex flex-col text-base max-h-60 overflow-y-auto">
+                    {route.hops.into_iter().enumerate().map(hop).collect::<Vec<_>>()}
+                </div>
+            }
+            .into_any(),
+            None if guild && target => view! {
+                <p class="text-base text-neutral-400">"No route found. The guild may already own the target."</p>
+            }
+            .into_any(),
+            None => view! {
+                <p class="text-base text-neutral-400">"Pick the attacking guild and a target territory."</p>
+            }
+            .into_any(),
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <button class="flex justify-between items-center text-lg" on:click={move |_| show.update(|s| *s = !*s)}>
+                <h2>"Attack route"</h2>
+                <Show when=move || !show.get()><icons::ChevronUp size=24/></Show>
+                <Show when=move || show.get()><icons::ChevronDown size=24/></Show>
+            </button>
+
+            <Show when=move || show.get()>
+                <GuildSelect state picked=planner.guild />
+
+                <div class="flex items-center gap-2 text-base">
+                    <span class="truncate flex-1">
+                        "Target: "{move || planner.target.get().map_or_else(|| String::from("none"), |t| t.to_string())}
+                    </span>
+                    <button
+                        class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg disabled:text-neutral-600"
+                        disabled=move || selected.read().is_none()
+                        on:click=move |_| planner.target.set(selected.get())
+                    >
+                        "Use selected"
+                    </button>
+                </div>
+
+                {details}
+            </Show>
+        </div>
+    }
+}
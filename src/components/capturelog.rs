@@ -0,0 +1,112 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{guild::Guild, terr::Territory};
+
+use crate::{
+    datasource::TerrCapture, sectimer::SecondTimer, settings::use_toggle, util::fmt_time_short,
+    wynnmap::context::MapPosition,
+};
+
+/// Collapsible list of the territory captures received while the map is open
+#[component]
+pub fn CaptureLog(
+    #[prop(into)] captures: Signal<VecDeque<TerrCapture>>,
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    selected: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let show = use_toggle("capturelog", true);
+    let filter = RwSignal::new(String::new());
+
+    let camera = expect_context::<MapPosition>();
+    let SecondTimer(now) = expect_context::<SecondTimer>();
+
+    let filtered = move || {
+        let filter = filter.read().trim().to_lowercase();
+        let matches = |g: &Guild| {
+            g.prefix.to_lowercase() == filter || g.name.to_lowercase().contains(&filter)
+        };
+
+        captures
+            .read()
+            .iter()
+            .filter(|c| filter.is_empty() || matches(&c.old) || matches(&c.new))
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    let fly_to = move |name: &Arc<str>| {
+        if let Some(terr) = terrs.read_untracked().get(name) {
+            camera.fit_region(terr.location);
+        }
+
+        selected.set(Some(name.clone()));
+    };
+
+    let guild_tag = |guild: &Guild| {
+        let col = guild.get_color();
+
+        view! {
+            <span class="font-mono" style:color=format!("rgb({} {} {})", col.0, col.1, col.2)>
+                "["{guild.prefix.to_string()}"]"
+            </span>
+        }
+    };
+
+    view! {
+        <div class="flex flex-col min-h-0">
+            <hr class="border-neutral-600" />
+            <div
+                class="flex justify-between items-center cursor-pointer text-xl p-2 py-1 hover:bg-neutral-700"
+                on:click=move |_| show.update(|s| *s = !*s)
+            >
+                <h2>"Captures"</h2>
+                <Show when=move || !show.get()><icons::ChevronUp size=24/></Show>
+                <Show when=move || show.get()><icons::ChevronDown size=24/></Show>
+            </div>
+            <div class="flex flex-col min-h-0" class:hidden=move || !show.get()>
+                <hr class="border-neutral-600" />
+                <input
+                    type="text"
+                    class="m-2 p-1 rounded border-1 border-neutral-600 text-base"
+                    placeholder="Filter by guild"
+                    bind:value=filter
+                />
+                <div class="overflow-y-auto max-h-60 text-base">
+                    <Show when={move || captures.read().is_empty()}>
+                        <p class="p-2 text-neutral-400">"No territories have been captured since the map was opened."</p>
+                    </Show>
+                    <For
+                        each=filtered
+                        key=|c| c.clone()
+                        children=move |c| {
+                            let time = c.time;
+                            let ago = move || fmt_time_short(now.read().duration_since(time));
+
+                            view! {
+                                <div
+                                    class="flex flex-col px-2 py-1 cursor-pointer hover:bg-neutral-700"
+                                    on:click={
+                                        let terr = c.terr.clone();
+                                        move |_| fly_to(&terr)
+                                    }
+                                >
+                                    <div class="flex justify-between gap-2">
+                                        <span class="truncate">{c.terr.to_string()}</span>
+                                        <span class="text-neutral-400 shrink-0">{ago}" ago"</span>
+                                    </div>
+                                    <div>
+                                        {guild_tag(&c.old)}" → "{guild_tag(&c.new)}
+                                    </div>
+                                </div>
+                            }
+                        }
+                    />
+                </div>
+            </div>
+        </div>
+    }
+}
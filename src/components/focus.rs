@@ -0,0 +1,77 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use leptos::prelude::*;
+
+use crate::{
+    components::checkbox::Checkbox,
+    settings::{use_list, use_toggle},
+};
+
+/// Tags of the guilds which are focused
+///
+/// While any guild is focused the territories of the other guilds are dimmed or hidden.
+pub fn use_focus() -> RwSignal<BTreeSet<Arc<str>>> {
+    use_list("focused_guilds")
+}
+
+/// Whether the territories of guilds which aren't focused are hidden instead of dimmed
+pub fn use_hide_unfocused() -> RwSignal<bool> {
+    use_toggle("hide_unfocused", false)
+}
+
+/// Button for focusing or unfocusing a guild
+#[component]
+pub fn FocusButton(#[prop(into)] prefix: Signal<Arc<str>>) -> impl IntoView {
+    let focus = use_focus();
+    let focused = move || focus.read().contains(&*prefix.read());
+
+    let toggle = move |e: leptos::ev::MouseEvent| {
+        // the button may be inside of a link
+        e.prevent_default();
+        e.stop_propagation();
+
+        let prefix = prefix.get();
+        focus.update(|f| {
+            if !f.remove(&prefix) {
+                f.insert(prefix);
+            }
+        });
+    };
+
+    view! {
+        <button
+            class="cursor-pointer"
+            class:text-neutral-500={move || !focused()}
+            title={move || if focused() { "Unfocus guild" } else { "Focus guild" }}
+            on:click=toggle
+        >
+            <icons::Focus size=20 />
+        </button>
+    }
+}
+
+/// Summary of the focused guilds with controls for clearing the focus
+#[component]
+pub fn FocusControls() -> impl IntoView {
+    let focus = use_focus();
+    let hide_unfocused = use_hide_unfocused();
+
+    view! {
+        <Show when={move || !focus.read().is_empty()}>
+            <div class="flex flex-col gap-1 p-2 text-base">
+                <div class="flex justify-between items-center">
+                    <span>
+                        "Focused: "
+                        <span class="font-mono">
+                            {move || focus.read().iter().map(|p| format!("[{p}]")).collect::<Vec<_>>().join(" ")}
+                        </span>
+                    </span>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| focus.set(BTreeSet::new())}>
+                        "Clear"
+                    </button>
+                </div>
+                <Checkbox id="hide_unfocused" checked={hide_unfocused}>"Hide other guilds"</Checkbox>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{
+    frontline::{FrontlineTerr, frontline},
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    components::guildselect::GuildSelect, sectimer::SecondTimer, settings::use_toggle,
+    util::fmt_time_short, wynnmap::highlight::HighlightView,
+};
+
+/// Highlight color of frontline territories which can be attacked
+const ATTACKABLE_COLOR: &str = "#ef4444";
+/// Highlight color of frontline territories on attack cooldown
+const COOLDOWN_COLOR: &str = "#a3a3a3";
+
+/// Ranked frontline of the guild picked in the frontline panel
+#[derive(Clone, Copy)]
+pub struct Frontline {
+    /// Tag of the picked guild
+    pub guild: RwSignal<Option<Arc<str>>>,
+    pub terrs: Memo<Vec<FrontlineTerr>>,
+}
+
+impl Frontline {
+    pub fn new(
+        terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+        state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    ) -> Self {
+        let SecondTimer(now) = expect_context::<SecondTimer>();
+        let show = use_frontline_toggle();
+        let guild = RwSignal::new(None::<Arc<str>>);
+
+        let front = Memo::new(move |_| {
+            let Some(prefix) = guild.get().filter(|_| show.get()) else {
+                return Vec::new();
+            };
+
+            frontline(&terrs.read(), &state.read(), &prefix, now.get())
+        });
+
+        Self {
+            guild,
+            terrs: front,
+        }
+    }
+}
+
+fn use_frontline_toggle() -> RwSignal<bool> {
+    use_toggle("frontline", false)
+}
+
+/// Map layer highlighting the frontline territories
+#[component]
+pub fn FrontlineView(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    frontline: Frontline,
+) -> impl IntoView {
+    let split = move |attackable: bool| {
+        Signal::derive(move || {
+            frontline
+                .terrs
+                .read()
+                .iter()
+                .filter(|t| t.attackable() == attackable)
+                .map(|t| t.name.clone())
+                .collect::<BTreeSet<_>>()
+        })
+    };
+
+    view! {
+        <HighlightView terrs highlighted={split(true)} color=ATTACKABLE_COLOR />
+        <HighlightView terrs highlighted={split(false)} color=COOLDOWN_COLOR />
+    }
+}
+
+/// Sidebar panel for picking a guild and listing its frontline from the best attack target
+#[component]
+pub fn FrontlinePanel(
+    #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    frontline: Frontline,
+    /// The territory selected on the map, set when a territory in the panel is clicked
+    selected: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let show = use_frontline_toggle();
+
+    let entry = move |t: FrontlineTerr, rank: usize| {
+        let status = if t.attackable() {
+            String::from("Attackable")
+        } else {
+            format!("Cooldown {}", fmt_time_short(t.cooldown))
+        };
+        let held = t.held.map_or_else(|| String::from("?"), fmt_time_short);
+        let name = t.name.clone();
+
+        view! {
+            <button
+                class="flex flex-col text-left px-1 hover:bg-neutral-700"
+                on:click=move |_| selected.set(Some(name.clone()))
+            >
+                <span class="truncate">{format!("{rank}. {} [{}]", t.name, t.owner.prefix)}</span>
+                <span class="text-sm text-neutral-400">
+                    <span class:text-red-500=t.attackable()>{status}</span>
+                    {format!(" · held {held} · ")}
+                    <span style:color=t.tier.color()>{t.tier.to_string()}</span>
+                    {format!(" · touches {}", t.touching)}
+                </span>
+            </button>
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <button class="flex justify-between items-center text-lg" on:click={move |_| show.update(|s| *s = !*s)}>
+                <h2>"Frontline"</h2>
+                <Show when=move || !show.get()><icons::ChevronUp size=24/></Show>
+                <Show when=move || show.get()><icons::ChevronDown size=24/></Show>
+            </button>
+
+            <Show when=move || show.get()>
+                <GuildSelect state picked=frontline.guild />
+
+                <div class="flex flex-col text-base max-h-60 overflow-y-auto">
+                    {move || frontline.terrs.get().into_iter().enumerate().map(|(i, t)| entry(t, i + 1)).collect::<Vec<_>>()}
+                </div>
+            </Show>
+        </div>
+    }
+}
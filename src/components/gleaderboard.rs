@@ -3,13 +3,21 @@ use std::{
     sync::Arc,
 };
 
-use leptos::prelude::*;
+use leptos::{ev::MouseEvent, prelude::*};
 use wynnmap_types::terr::TerrState;
 
+use crate::{
+    components::focus::{FocusButton, FocusControls, use_focus},
+    dialog::guild::GuildProfiles,
+};
+
 #[component]
 pub fn Gleaderboard(
     #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
     #[prop(into, default = RwSignal::new(true))] show_guild_leaderboard: RwSignal<bool>,
+    /// Show buttons for focusing guilds
+    #[prop(optional)]
+    focusable: bool,
 ) -> impl IntoView {
     view! {
         <div class="gleaderboard">
@@ -21,7 +29,8 @@ pub fn Gleaderboard(
             </div>
             <div class="content" class:hidden={move || !show_guild_leaderboard.get()}>
                 <hr/>
-                <GleaderboardContent state/>
+                {focusable.then(|| view! { <FocusControls/> })}
+                <GleaderboardContent state focusable/>
             </div>
         </div>
     }
@@ -30,7 +39,11 @@ pub fn Gleaderboard(
 #[component]
 fn GleaderboardContent(
     #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    focusable: bool,
 ) -> impl IntoView {
+    let focus = use_focus();
+    let profiles = use_context::<GuildProfiles>();
+
     let guild_leaderboard = move || {
         let mut guilds = HashMap::new();
 
@@ -51,7 +64,7 @@ fn GleaderboardContent(
     };
 
     view! {
-        <div class="content-inner">
+        <div class="content-inner" class:focusable=focusable>
             <For
                 each=move || guild_leaderboard().into_iter()
                 key=|(k, v)| (k.clone(), *v)
@@ -60,13 +73,29 @@ fn GleaderboardContent(
                     let col = format!("{}, {}, {}", col.0, col.1, col.2);
                     let name = k.name.clone();
                     let link = move || format!("https://wynncraft.com/stats/guild/{}", name.clone());
+                    let prefix = k.prefix.clone();
+                    let guild = k.clone();
+                    // open the profile instead of the link when the mode has profiles
+                    let open_profile = move |e: MouseEvent| {
+                        if let Some(profiles) = profiles
+                            && !(e.ctrl_key() || e.meta_key() || e.shift_key())
+                        {
+                            e.prevent_default();
+                            profiles.open(guild.clone());
+                        }
+                    };
+                    let focused = {
+                        let prefix = prefix.clone();
+                        move || focusable && focus.read().contains(&prefix)
+                    };
 
                     view! {
-                        <a class="glrow" style:--col=col href=link() target="_blank">
+                        <a class="glrow" class:focused=focused style:--col=col href=link() target="_blank" on:click=open_profile>
                             <div/>
                             <span>"["{k.prefix}"]"</span>
                             <span>{k.name}</span>
                             <span>{v}</span>
+                            {focusable.then(|| view! { <span><FocusButton prefix /></span> })}
                         </a>
                     }
                 }
@@ -0,0 +1,54 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{guild::Guild, terr::TerrState};
+
+/// Select for picking one of the guilds owning territories, sorted by their territory count
+#[component]
+pub fn GuildSelect(
+    #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    /// Tag of the picked guild
+    picked: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let guilds = Memo::new(move |_| {
+        let mut counts: HashMap<Guild, usize> = HashMap::new();
+
+        for s in state
+            .read()
+            .values()
+            .filter(|s| s.guild != Guild::default())
+        {
+            *counts.entry(s.guild.clone()).or_default() += 1;
+        }
+
+        let mut guilds: Vec<_> = counts.into_iter().collect();
+        guilds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.prefix.cmp(&b.0.prefix)));
+
+        guilds.into_iter().map(|(g, _)| g).collect::<Vec<_>>()
+    });
+
+    view! {
+        <select
+            class="p-1 rounded border-1 border-neutral-600 bg-neutral-900"
+            on:change:target=move |ev| {
+                let value = ev.target().value();
+                picked.set((!value.is_empty()).then(|| Arc::from(value)));
+            }
+        >
+            <option value="" selected={move || picked.read().is_none()}>"Pick a guild"</option>
+            {move || guilds.get().into_iter().map(|g| {
+                let prefix = g.prefix.clone();
+                let is_picked = move || picked.read().as_ref() == Some(&prefix);
+
+                view! {
+                    <option value={g.prefix.to_string()} selected=is_picked>
+                        {format!("{} [{}]", g.name, g.prefix)}
+                    </option>
+                }
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
@@ -0,0 +1,62 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::terr::TerrState;
+
+use crate::components::guildselect::GuildSelect;
+
+/// Sidebar controls for picking the guilds whose territories are highlighted
+#[component]
+pub fn HighlightPanel(
+    #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    /// Tags of the highlighted guilds
+    guilds: RwSignal<BTreeSet<Arc<str>>>,
+) -> impl IntoView {
+    let picked = RwSignal::new(None);
+
+    // picked guilds are added to the highlighted guilds and the select is reset for the next one
+    Effect::new(move || {
+        if let Some(tag) = picked.get() {
+            guilds.update(|g| _ = g.insert(tag));
+            picked.set(None);
+        }
+    });
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <div class="flex justify-between items-center text-lg">
+                <h2>"Highlight guilds"</h2>
+                <Show when={move || !guilds.read().is_empty()}>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg text-base" on:click={move |_| guilds.set(BTreeSet::new())}>
+                        "Clear"
+                    </button>
+                </Show>
+            </div>
+
+            <GuildSelect state picked />
+
+            <div class="flex flex-wrap gap-1 text-base font-mono">
+                <For
+                    each=move || guilds.get()
+                    key=|tag| tag.clone()
+                    children=move |tag| {
+                        let remove = {
+                            let tag = tag.clone();
+                            move |_| guilds.update(|g| _ = g.remove(&tag))
+                        };
+
+                        view! {
+                            <button class="flex items-center px-1 border-1 border-yellow-400 hover:bg-neutral-700 rounded" title="Remove highlight" on:click=remove>
+                                {format!("[{tag}]")}
+                                <icons::X size=16 />
+                            </button>
+                        }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
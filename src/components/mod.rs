@@ -1,5 +1,12 @@
+pub mod attackroute;
+pub mod capturelog;
 pub mod checkbox;
+pub mod focus;
+pub mod frontline;
 pub mod gleaderboard;
+pub mod guildselect;
+pub mod highlight;
 pub mod incrementor;
 pub mod sidebar;
 pub mod sidecard;
+pub mod watch;
@@ -4,6 +4,7 @@
 
 use leptos::prelude::*;
 
+pub mod routes;
 pub mod terr;
 
 /// Base sidecard component
@@ -0,0 +1,77 @@
+use std::sync::Arc;
+
+use leptos::prelude::*;
+use wynnmap_types::routing::TradeRoutes;
+
+use crate::components::checkbox::Checkbox;
+
+/// Displays the route of a territory to its guild hq and the resources flowing through it
+#[component]
+pub fn RouteInfo(
+    #[prop(into)] name: Signal<Arc<str>>,
+    #[prop(into)] routes: Signal<Option<TradeRoutes>>,
+    show_routes: RwSignal<bool>,
+) -> impl IntoView {
+    let info = move || {
+        let routes = routes.read();
+        let name = name.read();
+
+        let Some(routes) = routes.as_ref() else {
+            return view! {
+                <p class="text-neutral-400">"This guild has no HQ"</p>
+            }
+            .into_any();
+        };
+
+        if routes.cut_off.contains(&*name) {
+            return view! {
+                <p class="text-red-600">"Cut off from the HQ"</p>
+            }
+            .into_any();
+        }
+
+        let hops = routes.distance.get(&*name).copied().unwrap_or_default();
+        let hq = routes.hq.clone();
+        let flow = routes.flow.get(&*name).copied().unwrap_or_default();
+
+        let materials = [
+            (flow.emerald, "emeralds"),
+            (flow.ore, "ore"),
+            (flow.wood, "wood"),
+            (flow.fish, "fish"),
+            (flow.crop, "crops"),
+        ];
+
+        view! {
+            <p>
+                {if hops == 0 {
+                    String::from("Guild HQ")
+                } else {
+                    format!("{hops} hops to the HQ at {hq}")
+                }}
+            </p>
+            <h3 class="mt-1">"Resource flow:"</h3>
+            <div class="flowview">
+                {materials.into_iter()
+                    .filter(|(n, _)| *n > 0)
+                    .map(|(n, name)| view! {
+                        <div class={format!("spriteicon icon-{name}")}></div>
+                        <span>{format!("+{n}/h")}</span>
+                        <span>{name}</span>
+                    })
+                    .collect::<Vec<_>>()}
+            </div>
+        }
+        .into_any()
+    };
+
+    view! {
+        <div>
+            <h2 class="text-xl">"Trade route"</h2>
+            <div class="p-2">
+                <Checkbox id="traderoutes" checked={show_routes}>"Show trade routes"</Checkbox>
+                {info}
+            </div>
+        </div>
+    }
+}
@@ -6,7 +6,12 @@ use wynnmap_types::{
     terr::{TerrState, Territory},
 };
 
-use crate::{sectimer::SecondTimer, util::fmt_time_long};
+use crate::{
+    components::{focus::FocusButton, watch::WatchButton},
+    dialog::guild::GuildProfiles,
+    sectimer::SecondTimer,
+    util::fmt_time_long,
+};
 
 #[component]
 pub fn TerrStats(
@@ -17,7 +22,7 @@ pub fn TerrStats(
     let owner = move || state.read().get(&name.get()).cloned().unwrap_or_default();
 
     view! {
-        <TerrInfo name terrs state />
+        <TerrInfo name terrs state watchable=true />
 
         <GuildInfo state={Signal::derive(owner)} />
     }
@@ -29,10 +34,16 @@ pub fn TerrInfo(
     #[prop(into)] name: Signal<Arc<str>>,
     #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
     #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    /// Show a button for watching the territory
+    #[prop(optional)]
+    watchable: bool,
 ) -> impl IntoView {
     view! {
         <div>
-            <h1 class="text-2xl">{name}</h1>
+            <h1 class="text-2xl flex items-center gap-2">
+                {name}
+                {watchable.then(|| view! { <WatchButton name /> })}
+            </h1>
 
             <div class="resview">
                 {move ||
@@ -100,11 +111,29 @@ pub fn GuildInfo(#[prop(into)] state: Signal<TerrState>) -> impl IntoView {
             .map(|acq| now.read().duration_since(acq))
     });
 
+    let prefix = Signal::derive(move || state.read().guild.prefix.clone());
+
+    let profiles = use_context::<GuildProfiles>();
+    let has_profile = move || profiles.is_some() && state.read().guild != Guild::default();
+    let open_profile = move |_| {
+        if let Some(profiles) = profiles
+            && has_profile()
+        {
+            profiles.open(state.read_untracked().guild.clone());
+        }
+    };
+
     view! {
         <div>
-            <h1 class="text-xl">
-                {move || state.read().guild.name.clone()}" "
-                <span class="font-mono">"["{move || state.read().guild.prefix.clone()}"]"</span>
+            <h1 class="text-xl flex items-center gap-2">
+                <span class:cursor-pointer=has_profile class=("hover:underline", has_profile) on:click=open_profile>
+                    {move || state.read().guild.name.clone()}" "
+                    <span class="font-mono">"["{prefix}"]"</span>
+                </span>
+                <Show when={move || state.read().guild != Guild::default()}>
+                    <FocusButton prefix />
+                    <WatchButton name=prefix guild=true />
+                </Show>
             </h1>
 
             {move || time.get().map(|time| view! {
@@ -0,0 +1,335 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    sync::Arc,
+};
+
+use jiff::{SignedDuration, Timestamp};
+use leptos::{prelude::*, task::spawn_local};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AudioContext, Notification, NotificationOptions, NotificationPermission, js_sys::Reflect,
+};
+use wynnmap_types::{frontline::ATTACK_COOLDOWN, guild::Guild, terr::TerrState};
+
+use crate::{
+    components::checkbox::Checkbox,
+    sectimer::SecondTimer,
+    settings::{use_list, use_toggle},
+};
+
+/// How long before the end of the attack cooldown of a watched territory the alert is sent
+const COOLDOWN_WARNING: SignedDuration = SignedDuration::from_mins(1);
+
+thread_local! {
+    /// Audio context playing the alert sounds, kept open as browsers limit how many contexts can exist
+    static AUDIO: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Names of the watched territories
+pub fn use_watched_terrs() -> RwSignal<BTreeSet<Arc<str>>> {
+    use_list("watched_terrs")
+}
+
+/// Tags of the watched guilds
+pub fn use_watched_guilds() -> RwSignal<BTreeSet<Arc<str>>> {
+    use_list("watched_guilds")
+}
+
+/// Whether alerts also play a sound
+pub fn use_watch_sound() -> RwSignal<bool> {
+    use_toggle("watch_sound", false)
+}
+
+/// Button for watching or unwatching a territory or a guild
+#[component]
+pub fn WatchButton(
+    /// Name of the territory or tag of the guild
+    #[prop(into)]
+    name: Signal<Arc<str>>,
+    /// Whether the name is the tag of a guild
+    #[prop(optional)]
+    guild: bool,
+) -> impl IntoView {
+    let list = if guild {
+        use_watched_guilds()
+    } else {
+        use_watched_terrs()
+    };
+    let watched = move || list.read().contains(&*name.read());
+
+    let toggle = move |e: leptos::ev::MouseEvent| {
+        e.prevent_default();
+        e.stop_propagation();
+
+        let name = name.get();
+        list.update(|l| {
+            if !l.remove(&name) {
+                l.insert(name);
+            }
+        });
+
+        // ask for the permission when it is first needed
+        request_permission(None);
+    };
+
+    view! {
+        <button
+            class="cursor-pointer"
+            class:text-neutral-500={move || !watched()}
+            title={move || match (watched(), guild) {
+                (true, true) => "Unwatch guild",
+                (false, true) => "Watch guild",
+                (true, false) => "Unwatch territory",
+                (false, false) => "Watch territory",
+            }}
+            on:click=toggle
+        >
+            <icons::Bell size=20 />
+        </button>
+    }
+}
+
+/// Sends alerts about the watched territories and guilds
+#[component]
+pub fn Watcher(#[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>) -> impl IntoView {
+    let SecondTimer(now) = expect_context::<SecondTimer>();
+
+    let terrs = use_watched_terrs();
+    let guilds = use_watched_guilds();
+    let sound = use_watch_sound();
+
+    // owners from the previous update
+    let owners = StoredValue::new(BTreeMap::<Arc<str>, Guild>::new());
+
+    Effect::new(move || {
+        let state = state.read();
+        let current: BTreeMap<_, _> = state
+            .iter()
+            .map(|(n, s)| (n.clone(), s.guild.clone()))
+            .collect();
+
+        let previous = owners
+            .try_update_value(|o| std::mem::replace(o, current))
+            .unwrap_or_default();
+
+        // the first data isn't a change
+        if previous.is_empty() {
+            return;
+        }
+
+        let terrs = terrs.read_untracked();
+        let guilds = guilds.read_untracked();
+
+        for (name, old) in &previous {
+            let Some(new) = state.get(name).map(|s| &s.guild) else {
+                continue;
+            };
+
+            if old.prefix == new.prefix {
+                continue;
+            }
+
+            if terrs.contains(name) {
+                alert(
+                    &format!("{name} changed owner"),
+                    &format!(
+                        "Taken by {} [{}] from [{}]",
+                        new.name, new.prefix, old.prefix
+                    ),
+                    sound.get_untracked(),
+                );
+            } else if guilds.contains(&old.prefix) {
+                alert(
+                    &format!("{} lost {name}", old.name),
+                    &format!("Taken by {} [{}]", new.name, new.prefix),
+                    sound.get_untracked(),
+                );
+            }
+        }
+    });
+
+    // cooldowns which have already been alerted about
+    let alerted = StoredValue::new(HashSet::<(Arc<str>, Timestamp)>::new());
+
+    Effect::new(move || {
+        let now = now.get();
+        let state = state.read_untracked();
+
+        for name in terrs.read().iter() {
+            let Some(s) = state.get(name) else {
+                continue;
+            };
+            let Some(acquired) = s.acquired else {
+                continue;
+            };
+
+            let remaining = ATTACK_COOLDOWN - now.duration_since(acquired);
+
+            if remaining <= SignedDuration::ZERO || remaining > COOLDOWN_WARNING {
+                continue;
+            }
+
+            if alerted
+                .try_update_value(|a| a.insert((name.clone(), acquired)))
+                .unwrap_or(false)
+            {
+                alert(
+                    &format!("{name} can be attacked soon"),
+                    &format!(
+                        "The attack cooldown of [{}] ends in {}s",
+                        s.guild.prefix,
+                        remaining.as_secs()
+                    ),
+                    sound.get_untracked(),
+                );
+            }
+        }
+    });
+}
+
+/// Sidebar panel listing the watched territories and guilds
+#[component]
+pub fn WatchPanel() -> impl IntoView {
+    let terrs = use_watched_terrs();
+    let guilds = use_watched_guilds();
+    let sound = use_watch_sound();
+
+    let permission = RwSignal::new(notification_permission());
+
+    let entry = move |list: RwSignal<BTreeSet<Arc<str>>>, name: Arc<str>, label: String| {
+        view! {
+            <div class="flex justify-between items-center">
+                <span class="truncate">{label}</span>
+                <button class="cursor-pointer p-1" title="Unwatch" on:click=move |_| {
+                    list.update(|l| {
+                        l.remove(&name);
+                    });
+                }>
+                    <icons::X size=16 />
+                </button>
+            </div>
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-1 p-2 text-base">
+            <h2 class="text-xl">"Watchlist"</h2>
+
+            <Show when={move || terrs.read().is_empty() && guilds.read().is_empty()}>
+                <p class="text-neutral-400">"Watch territories and guilds with the bell in the territory info to get alerts about them."</p>
+            </Show>
+
+            {move || terrs.get().into_iter().map(|t| entry(terrs, t.clone(), t.to_string())).collect::<Vec<_>>()}
+            {move || guilds.get().into_iter().map(|g| entry(guilds, g.clone(), format!("[{g}]"))).collect::<Vec<_>>()}
+
+            // enabling the sound is a user gesture which allows the audio context to start
+            <Checkbox id="watch_sound" checked={sound} on:change=move |_| {
+                if sound.get_untracked() {
+                    audio_context();
+                }
+            }>
+                "Play a sound"
+            </Checkbox>
+
+            {move || match permission.get() {
+                Some(NotificationPermission::Granted) => None,
+                Some(NotificationPermission::Denied) => Some(view! {
+                    <p class="text-neutral-400">"Notifications are blocked by the browser."</p>
+                }.into_any()),
+                Some(_) => Some(view! {
+                    <button
+                        class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg"
+                        on:click=move |_| request_permission(Some(permission))
+                    >
+                        "Enable notifications"
+                    </button>
+                }.into_any()),
+                None => Some(view! {
+                    <p class="text-neutral-400">"Notifications aren't supported by this browser."</p>
+                }.into_any()),
+            }}
+        </div>
+    }
+}
+
+/// The notification permission or none if notifications aren't supported
+fn notification_permission() -> Option<NotificationPermission> {
+    Reflect::has(&window(), &"Notification".into())
+        .unwrap_or(false)
+        .then(Notification::permission)
+}
+
+/// Ask for the permission to show notifications if it hasn't been asked for yet
+fn request_permission(result: Option<RwSignal<Option<NotificationPermission>>>) {
+    if notification_permission() != Some(NotificationPermission::Default) {
+        return;
+    }
+
+    let Ok(promise) = Notification::request_permission() else {
+        return;
+    };
+
+    spawn_local(async move {
+        let _ = JsFuture::from(promise).await;
+
+        if let Some(result) = result {
+            result.set(notification_permission());
+        }
+    });
+}
+
+/// Show a notification and optionally play a sound
+fn alert(title: &str, body: &str, sound: bool) {
+    if notification_permission() == Some(NotificationPermission::Granted) {
+        let options = NotificationOptions::new();
+        options.set_body(body);
+
+        let _ = Notification::new_with_options(title, &options);
+    }
+
+    if sound {
+        beep();
+    }
+}
+
+/// The audio context for alert sounds, created when it is first needed
+///
+/// Contexts created without a user gesture start suspended so it is resumed every time, which works once the user has
+/// interacted with the page.
+fn audio_context() -> Option<AudioContext> {
+    AUDIO.with_borrow_mut(|audio| {
+        if audio.is_none() {
+            *audio = AudioContext::new().ok();
+        }
+
+        let ctx = audio.clone()?;
+        let _ = ctx.resume();
+
+        Some(ctx)
+    })
+}
+
+/// Play a short beep
+fn beep() {
+    let Some(ctx) = audio_context() else {
+        return;
+    };
+
+    let (Ok(osc), Ok(gain)) = (ctx.create_oscillator(), ctx.create_gain()) else {
+        return;
+    };
+
+    let t = ctx.current_time();
+
+    osc.frequency().set_value(880.0);
+    let _ = gain.gain().set_value_at_time(0.2, t);
+    let _ = gain
+        .gain()
+        .exponential_ramp_to_value_at_time(0.001, t + 0.4);
+
+    let _ = osc.connect_with_audio_node(&gain);
+    let _ = gain.connect_with_audio_node(&ctx.destination());
+    let _ = osc.start();
+    let _ = osc.stop_with_when(t + 0.4);
+}
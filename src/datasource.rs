@@ -1,21 +1,26 @@
 use std::{
-    collections::{BTreeMap, btree_map::Entry},
+    collections::{BTreeMap, VecDeque, btree_map::Entry},
     sync::Arc,
     time::Duration,
 };
 
 use codee::{Decoder, Encoder};
 use gloo_net::http::Request;
+use jiff::Timestamp;
 use leptos::{
     logging::{error, warn},
     prelude::*,
 };
 use leptos_use::{
-    UseWebSocketOptions, UseWebSocketReturn, core::ConnectionReadyState, use_websocket_with_options,
+    ReconnectLimit, UseWebSocketOptions, UseWebSocketReturn, core::ConnectionReadyState,
+    use_websocket_with_options,
 };
 use wynnmap_types::{
     gather::{GatherSpots, MatData},
+    guild::Guild,
     maptile::MapTile,
+    plan::StoredPlan,
+    session::{SessionClientMessage, SessionServerMessage},
     terr::{MapState, TerrState, TerrTimestamps, Territory},
     ws::TerrSockMessage,
 };
@@ -48,9 +53,69 @@ pub async fn get_state() -> Result<MapState, gloo_net::Error> {
     Ok(resp)
 }
 
+/// Store a plan share string on the server to get a short link to it
+///
+/// Plans are deleted after `expires_days` days if given.
+pub async fn store_plan(
+    payload: String,
+    expires_days: Option<u32>,
+) -> Result<StoredPlan, gloo_net::Error> {
+    let url = expires_days.map_or_else(
+        || String::from("/api/v3/plans"),
+        |d| format!("/api/v3/plans?expires={d}"),
+    );
+
+    let resp = Request::post(&url)
+        .header("Content-Type", "text/plain")
+        .body(payload)?
+        .send()
+        .await?;
+
+    if !resp.ok() {
+        return Err(gloo_net::Error::GlooError(format!(
+            "{} {}",
+            resp.status(),
+            resp.text().await?
+        )));
+    }
+
+    resp.json().await
+}
+
+/// Get the share string of a plan stored on the server
+pub async fn get_plan(id: &str) -> Result<String, gloo_net::Error> {
+    let resp = Request::get(&format!("/api/v3/plans/{id}")).send().await?;
+
+    if !resp.ok() {
+        return Err(gloo_net::Error::GlooError(format!(
+            "{} {}",
+            resp.status(),
+            resp.text().await?
+        )));
+    }
+
+    resp.text().await
+}
+
+/// Maximum number of territory captures kept
+const MAX_CAPTURES: usize = 500;
+
+/// A change of the owner of a territory
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TerrCapture {
+    pub terr: Arc<str>,
+    pub old: Guild,
+    pub new: Guild,
+    pub time: Timestamp,
+}
+
+/// Keep the territory state updated over a websocket
+///
+/// Ownership changes are added to the front of `captures`.
 pub fn ws_terr_updates(
     state: RwSignal<BTreeMap<Arc<str>, TerrState>>,
     last_updated: RwSignal<TerrTimestamps>,
+    captures: RwSignal<VecDeque<TerrCapture>>,
 ) {
     let UseWebSocketReturn {
         ready_state,
@@ -83,6 +148,8 @@ pub fn ws_terr_updates(
         if let Some(msg) = message.get() {
             match msg {
                 TerrSockMessage::Update(updates, timestamps) => {
+                    let mut new_captures = Vec::new();
+
                     state.update(|s| {
                         for (name, data) in updates {
                             let e = s.entry(name.clone());
@@ -92,13 +159,35 @@ pub fn ws_terr_updates(
                                     warn!("Insering default data for territory {name}");
                                     vacant_entry.insert(TerrState::default()).apply_diff(data);
                                 }
-                                Entry::Occupied(mut occupied_entry) => {
-                                    occupied_entry.get_mut().apply_diff(data);
+                                Entry::Occupied(occupied_entry) => {
+                                    let terr = occupied_entry.into_mut();
+                                    let old = terr.guild.clone();
+
+                                    terr.apply_diff(data);
+
+                                    // only a known territory changing owners is a capture
+                                    if terr.guild.prefix != old.prefix {
+                                        new_captures.push(TerrCapture {
+                                            terr: name,
+                                            old,
+                                            new: terr.guild.clone(),
+                                            time: terr.acquired.unwrap_or_else(Timestamp::now),
+                                        });
+                                    }
                                 }
                             }
                         }
                     });
 
+                    if !new_captures.is_empty() {
+                        captures.update(|c| {
+                            for capture in new_captures {
+                                c.push_front(capture);
+                            }
+                            c.truncate(MAX_CAPTURES);
+                        });
+                    }
+
                     last_updated.set(timestamps);
                 }
                 TerrSockMessage::LastUpdate(timestamps) => {
@@ -109,14 +198,38 @@ pub fn ws_terr_updates(
     });
 }
 
+/// Function sending a message to a session
+pub type SessionSender = Arc<dyn Fn(&SessionClientMessage) + Send + Sync>;
+
+/// Connect to a collaborative planning session
+///
+/// Returns the state of the connection and a function for sending messages to the session. The connection is closed
+/// when the current reactive owner is cleaned up.
+pub fn ws_session(
+    room: &str,
+    on_message: impl Fn(&SessionServerMessage) + Send + Sync + 'static,
+) -> (Signal<ConnectionReadyState>, SessionSender) {
+    let UseWebSocketReturn {
+        ready_state, send, ..
+    } = use_websocket_with_options::<SessionClientMessage, SessionServerMessage, WynnmapCodec, _, _>(
+        &format!("/api/v3/sessions/{room}/ws"),
+        UseWebSocketOptions::default()
+            .reconnect_limit(ReconnectLimit::Infinite)
+            .on_message(on_message)
+            .on_error(|e| error!("Session websocket error:\n{e:?}")),
+    );
+
+    (ready_state, Arc::new(send))
+}
+
 struct WynnmapCodec;
 
 impl<T: serde::Serialize> Encoder<T> for WynnmapCodec {
-    type Error = ();
+    type Error = rmp_serde::encode::Error;
     type Encoded = Vec<u8>;
 
-    fn encode(_: &T) -> Result<Self::Encoded, Self::Error> {
-        panic!("Serialization is not used")
+    fn encode(val: &T) -> Result<Self::Encoded, Self::Error> {
+        wynnmap_types::encoding::encode_data(val)
     }
 }
 
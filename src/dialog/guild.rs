@@ -0,0 +1,293 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use leptos::prelude::*;
+use wynnmap_types::{
+    guild::Guild,
+    resources::Resources,
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    components::focus::FocusButton,
+    dialog::{DialogCloseButton, Dialogs},
+    sectimer::SecondTimer,
+    util::fmt_time_short,
+    wynnmap::context::MapPosition,
+};
+
+/// Data used by the guild profile dialogs of the current mode
+///
+/// Components showing guilds open a profile when this is provided and fall back to linking to wynncraft.com otherwise.
+#[derive(Clone, Copy)]
+pub struct GuildProfiles {
+    terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    /// Tag of the owner of every territory when the data was first loaded
+    initial: RwSignal<Option<BTreeMap<Arc<str>, Arc<str>>>>,
+    selected: RwSignal<Option<Arc<str>>>,
+}
+
+impl GuildProfiles {
+    /// Open the profile dialog of a guild
+    pub fn open(self, guild: Guild) {
+        let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+        dialogs.add(format!("guild_{}", guild.prefix), move || {
+            guild_dialog(guild.clone(), self)
+        });
+    }
+}
+
+/// Provide guild profiles for the territories of the current mode
+pub fn provide_guild_profiles(
+    terrs: impl Into<Signal<BTreeMap<Arc<str>, Territory>>>,
+    state: impl Into<Signal<BTreeMap<Arc<str>, TerrState>>>,
+    selected: RwSignal<Option<Arc<str>>>,
+) {
+    let state = state.into();
+    let initial = RwSignal::new(None);
+
+    // remember the owners of the first data loaded so that changes during this session can be shown
+    Effect::new(move || {
+        if initial.read_untracked().is_some() || state.read().is_empty() {
+            return;
+        }
+
+        initial.set(Some(
+            state
+                .read()
+                .iter()
+                .map(|(n, s)| (n.clone(), s.guild.prefix.clone()))
+                .collect(),
+        ));
+    });
+
+    provide_context(GuildProfiles {
+        terrs: terrs.into(),
+        state,
+        initial,
+        selected,
+    });
+}
+
+/// Dialog showing the territories, resources and recent changes of a guild
+fn guild_dialog(guild: Guild, profiles: GuildProfiles) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+    let camera = expect_context::<MapPosition>();
+    let SecondTimer(now) = expect_context::<SecondTimer>();
+
+    let GuildProfiles {
+        terrs,
+        state,
+        initial,
+        selected,
+    } = profiles;
+
+    let prefix: Arc<str> = guild.prefix.clone();
+
+    let owned = Memo::new({
+        let prefix = prefix.clone();
+        move |_| {
+            state
+                .read()
+                .iter()
+                .filter(|(_, s)| s.guild.prefix == prefix)
+                .map(|(n, s)| (n.clone(), s.clone()))
+                .collect::<Vec<_>>()
+        }
+    });
+
+    let hq = move || {
+        owned
+            .read()
+            .iter()
+            .find(|(_, s)| s.hq)
+            .map(|(n, _)| n.clone())
+    };
+
+    let resources = Memo::new(move |_| {
+        owned
+            .read()
+            .iter()
+            .map(|(_, s)| s.resources.clone())
+            .sum::<Resources>()
+    });
+
+    // territories gained and lost since the data was first loaded
+    let changes = Memo::new({
+        let prefix = prefix.clone();
+        move |_| {
+            let initial = initial.read();
+            let initial = initial.as_ref()?;
+            let state = state.read();
+
+            let gained: Vec<_> = state
+                .iter()
+                .filter(|(n, s)| {
+                    s.guild.prefix == prefix && initial.get(*n).is_none_or(|p| *p != prefix)
+                })
+                .map(|(n, _)| n.clone())
+                .collect();
+            let lost: Vec<_> = initial
+                .iter()
+                .filter(|(n, p)| {
+                    **p == prefix && state.get(*n).is_none_or(|s| s.guild.prefix != prefix)
+                })
+                .map(|(n, _)| n.clone())
+                .collect();
+
+            Some((gained, lost))
+        }
+    });
+
+    // fly to a territory and select it
+    let fly_to = move |name: Arc<str>| {
+        if let Some(terr) = terrs.read_untracked().get(&name) {
+            camera.fit_region(terr.location);
+        }
+
+        selected.set(Some(name));
+        dialogs.close();
+    };
+
+    let col = guild.get_color();
+    let col = format!("rgb({} {} {})", col.0, col.1, col.2);
+    let link = format!("https://wynncraft.com/stats/guild/{}", guild.name);
+
+    let res_row = |name: &'static str, values: fn(&Resources) -> (i32, i32, i32)| {
+        view! {
+            <div class={format!("spriteicon icon-{name}")}></div>
+            <span class="text-right">{move || format!("+{}/h", values(&resources.read()).0)}</span>
+            <span class="text-right">{move || values(&resources.read()).1}</span>
+            <span>"/"</span>
+            <span>{move || values(&resources.read()).2}</span>
+            <span>{name}</span>
+        }
+    };
+
+    let change_list = move |terrs: Vec<Arc<str>>, class: &'static str| {
+        terrs
+            .into_iter()
+            .map(|n| {
+                view! {
+                    <li class={format!("{class} cursor-pointer hover:underline")} on:click={
+                        let n = n.clone();
+                        move |_| fly_to(n.clone())
+                    }>{n.to_string()}</li>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-3xl h-dvh md:max-h-150 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center gap-2">
+                    <h1 class="text-4xl flex items-center gap-2 min-w-0">
+                        <span class="inline-block w-2 self-stretch rounded-sm" style:background-color=col></span>
+                        <span class="truncate">{guild.name.to_string()}</span>
+                        <span class="font-mono">"["{guild.prefix.to_string()}"]"</span>
+                        <FocusButton prefix={Signal::derive({
+                            let prefix = prefix.clone();
+                            move || prefix.clone()
+                        })} />
+                    </h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="p-2 overflow-y-auto grow flex flex-col gap-4">
+                <div class="flex flex-wrap gap-4 items-center">
+                    <span class="text-xl">{move || owned.read().len()}" territories"</span>
+                    {move || changes.get().map(|(gained, lost)| view! {
+                        <span class="text-green-500">"+"{gained.len()}</span>
+                        <span class="text-red-500">"-"{lost.len()}</span>
+                        <span class="text-neutral-400">"this session"</span>
+                    })}
+                    <a class="underline text-neutral-400" href=link target="_blank">"View on wynncraft.com"</a>
+                </div>
+
+                <div>
+                    <h2 class="text-2xl">"HQ"</h2>
+                    {move || match hq() {
+                        Some(hq) => view! {
+                            <div class="flex items-center gap-2 p-1">
+                                <span>{hq.to_string()}</span>
+                                <button
+                                    class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg"
+                                    on:click=move |_| fly_to(hq.clone())
+                                >"Fly to"</button>
+                            </div>
+                        }.into_any(),
+                        None => view! { <p class="p-1 text-neutral-400">"Unknown"</p> }.into_any(),
+                    }}
+                </div>
+
+                <div>
+                    <h2 class="text-2xl">"Resources"</h2>
+                    <div class="resview">
+                        {res_row("emeralds", |r| r.emerald.clone().into())}
+                        {res_row("ore", |r| r.ore.clone().into())}
+                        {res_row("wood", |r| r.wood.clone().into())}
+                        {res_row("fish", |r| r.fish.clone().into())}
+                        {res_row("crops", |r| r.crop.clone().into())}
+                    </div>
+                </div>
+
+                {move || changes.get().filter(|(g, l)| !g.is_empty() || !l.is_empty()).map(|(gained, lost)| view! {
+                    <div>
+                        <h2 class="text-2xl">"Changes this session"</h2>
+                        <ul class="p-1">
+                            {change_list(gained, "text-green-500")}
+                            {change_list(lost, "text-red-500")}
+                        </ul>
+                    </div>
+                })}
+
+                <div>
+                    <h2 class="text-2xl">"Territories"</h2>
+                    <table class="table-auto w-full border-separate border-spacing-0">
+                        <thead class="sticky top-0 bg-neutral-900">
+                            <tr class="divide-x-1 divide-neutral-600">
+                                <th class="border-b-1 border-neutral-600 p-1">"Territory"</th>
+                                <th class="border-b-1 border-neutral-600 p-1">"Time held"</th>
+                                <th class="border-b-1 border-neutral-600 p-1">"Treasury"</th>
+                                <th class="border-b-1 border-neutral-600 p-1">"Defences"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            <For
+                                each=move || owned.get()
+                                key=|(n, s)| (n.clone(), s.clone())
+                                children=move |(name, s)| {
+                                    let acquired = s.acquired;
+                                    let held = move || {
+                                        acquired.map(|acq| fmt_time_short(now.read().duration_since(acq)))
+                                    };
+
+                                    view! {
+                                        <tr class="divide-x-1 divide-neutral-600 hover:bg-neutral-800 cursor-pointer" on:click={
+                                            let name = name.clone();
+                                            move |_| fly_to(name.clone())
+                                        }>
+                                            <td class="border-b-1 border-neutral-600 p-1">
+                                                {name.to_string()}
+                                                {s.hq.then_some(" (HQ)")}
+                                            </td>
+                                            <td class="border-b-1 border-neutral-600 p-1 text-right">{held}</td>
+                                            <td class="border-b-1 border-neutral-600 p-1" style:color=s.treasury.color()>{s.treasury.to_string()}</td>
+                                            <td class="border-b-1 border-neutral-600 p-1" style:color=s.defences.color()>{s.defences.to_string()}</td>
+                                        </tr>
+                                    }
+                                }
+                            />
+                        </tbody>
+                    </table>
+                </div>
+            </div>
+        </div>
+    }
+}
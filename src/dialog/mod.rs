@@ -2,9 +2,12 @@ use std::sync::Arc;
 
 use leptos::prelude::*;
 
+pub mod guild;
 pub mod info;
+pub mod palette;
 pub mod planning;
 pub mod settings;
+pub mod terrtable;
 
 #[derive(Clone, Copy)]
 pub struct Dialogs {
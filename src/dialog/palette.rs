@@ -0,0 +1,391 @@
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use leptos::{ev, html, prelude::*};
+use web_sys::{HtmlElement, KeyboardEvent, wasm_bindgen::JsCast};
+use wynnmap_types::{
+    gather::GatherSpots,
+    guild::Guild,
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    dialog::{DialogCloseButton, Dialogs},
+    util::titlecase,
+    wynnmap::context::MapPosition,
+};
+
+/// Name of the palette dialog
+const PALETTE: &str = "command_palette";
+/// Maximum number of results shown
+const MAX_RESULTS: usize = 30;
+
+/// Zoom level used when flying to coordinates or gathering spots
+const POINT_ZOOM: f64 = 4.0;
+
+/// The things which can be searched for with the command palette
+#[derive(Clone, Copy)]
+pub struct PaletteSources {
+    pub terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    pub state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    pub spots: Signal<GatherSpots>,
+    /// Selected territory of the current mode
+    pub selected: Option<RwSignal<Option<Arc<str>>>>,
+    /// Selected gathering spot of the current mode
+    pub selected_spot: Option<RwSignal<Option<[i32; 2]>>>,
+}
+
+#[derive(Clone, PartialEq)]
+enum PaletteResult {
+    Territory(Arc<str>),
+    Guild(Guild),
+    /// Index of the material in the gathering spot resources
+    Material(usize, Arc<str>),
+    Coords([i32; 2]),
+}
+
+impl PaletteResult {
+    fn label(&self) -> String {
+        match self {
+            Self::Territory(name) => name.to_string(),
+            Self::Guild(guild) => format!("{} [{}]", guild.name, guild.prefix),
+            Self::Material(_, name) => titlecase(name),
+            Self::Coords([x, z]) => format!("{x} {z}"),
+        }
+    }
+
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::Territory(_) => "Territory",
+            Self::Guild(_) => "Guild",
+            Self::Material(..) => "Material",
+            Self::Coords(_) => "Coordinates",
+        }
+    }
+}
+
+/// Opens the command palette with Ctrl+K or "/"
+#[component]
+pub fn CommandPalette(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into, optional)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    #[prop(into, optional)] spots: Signal<GatherSpots>,
+    #[prop(optional)] selected: Option<RwSignal<Option<Arc<str>>>>,
+    #[prop(optional)] selected_spot: Option<RwSignal<Option<[i32; 2]>>>,
+) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let sources = PaletteSources {
+        terrs,
+        state,
+        spots,
+        selected,
+        selected_spot,
+    };
+
+    let handle = window_event_listener(ev::keydown, move |e: KeyboardEvent| {
+        let in_text_field = e
+            .target()
+            .and_then(|t| t.dyn_into::<HtmlElement>().ok())
+            .is_some_and(|t| matches!(t.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+
+        let open = ((e.ctrl_key() || e.meta_key()) && e.key().eq_ignore_ascii_case("k"))
+            || (e.key() == "/" && !in_text_field);
+
+        if !open {
+            return;
+        }
+
+        e.prevent_default();
+
+        if !dialogs.contains(PALETTE) {
+            dialogs.add(PALETTE, move || command_palette(sources));
+        }
+    });
+    on_cleanup(move || handle.remove());
+}
+
+/// Dialog for searching the map and flying to the results
+pub fn command_palette(sources: PaletteSources) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+    let camera = expect_context::<MapPosition>();
+
+    let query = RwSignal::new(String::new());
+    let active = RwSignal::new(0usize);
+
+    let results = Memo::new(move |_| search(&query.read(), &sources));
+
+    let input = NodeRef::<html::Input>::new();
+    Effect::new(move || {
+        if let Some(input) = input.get() {
+            let _ = input.focus();
+        }
+    });
+
+    let choose = move |result: &PaletteResult| {
+        go_to(result, &sources, camera);
+        dialogs.close();
+    };
+
+    let keydown = move |e: KeyboardEvent| {
+        // keep the map shortcuts from reacting to typing
+        e.stop_propagation();
+
+        let count = results.read().len();
+
+        match e.key().as_str() {
+            "ArrowDown" if count > 0 => active.update(|a| *a = (*a + 1) % count),
+            "ArrowUp" if count > 0 => active.update(|a| *a = (*a + count - 1) % count),
+            "Enter" => {
+                let result = results.read().get(active.get()).cloned();
+
+                if let Some(result) = result {
+                    choose(&result);
+                }
+            }
+            "Escape" => dialogs.close(),
+            _ => return,
+        }
+
+        e.prevent_default();
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-2xl max-h-dvh md:max-h-150 flex flex-col">
+            <div class="flex items-center gap-2 p-2">
+                <icons::Search size=24 />
+                <input
+                    type="text"
+                    class="p-2 flex-1 outline-none"
+                    placeholder="Search territories, guilds, materials or \"x z\" coordinates"
+                    node_ref=input
+                    bind:value=query
+                    on:input=move |_| active.set(0)
+                    on:keydown=keydown
+                />
+                <DialogCloseButton />
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="overflow-y-auto shrink">
+                <Show when={move || !query.read().trim().is_empty() && results.read().is_empty()}>
+                    <p class="p-2 text-neutral-400">"No results"</p>
+                </Show>
+
+                {move || results.get().into_iter().enumerate().map(|(i, result)| {
+                    let label = result.label();
+                    let kind = result.kind();
+
+                    view! {
+                        <div
+                            class="flex justify-between items-center p-2 px-3 cursor-pointer hover:bg-neutral-700"
+                            class:bg-neutral-800={move || active.get() == i}
+                            on:click=move |_| choose(&result)
+                        >
+                            <span class="truncate">{label}</span>
+                            <span class="text-sm text-neutral-400 shrink-0">{kind}</span>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }
+}
+
+/// Fly the map to a result and select it
+fn go_to(result: &PaletteResult, sources: &PaletteSources, camera: MapPosition) {
+    match result {
+        PaletteResult::Territory(name) => {
+            if let Some(terr) = sources.terrs.read_untracked().get(name) {
+                camera.fit_region(terr.location);
+            }
+
+            if let Some(selected) = sources.selected {
+                selected.set(Some(name.clone()));
+            }
+        }
+        PaletteResult::Guild(guild) => {
+            let state = sources.state.read_untracked();
+            let terrs = sources.terrs.read_untracked();
+            let owned: Vec<_> = state
+                .iter()
+                .filter(|(_, s)| s.guild.prefix == guild.prefix)
+                .collect();
+
+            // show the whole claim and select the hq or any territory if there is no hq
+            camera.fit_regions(
+                owned
+                    .iter()
+                    .filter_map(|(n, _)| Some(terrs.get(*n)?.location)),
+            );
+
+            let terr = owned
+                .iter()
+                .find(|(_, s)| s.hq)
+                .or_else(|| owned.first())
+                .map(|(n, _)| Arc::clone(n));
+
+            if let Some(selected) = sources.selected
+                && terr.is_some()
+            {
+                selected.set(terr);
+            }
+        }
+        PaletteResult::Material(res, _) => {
+            // go to the closest spot of the material
+            let center = camera.center();
+            let dist =
+                |p: [i32; 2]| (f64::from(p[0]) - center[0]).hypot(f64::from(p[1]) - center[1]);
+
+            let spot = sources
+                .spots
+                .read_untracked()
+                .spots
+                .iter()
+                .filter(|s| s.resource == *res)
+                .map(|s| [s.pos[0], s.pos[2]])
+                .min_by(|a, b| dist(*a).total_cmp(&dist(*b)));
+
+            if let Some(spot) = spot {
+                camera.fly_to(spot.map(f64::from), POINT_ZOOM);
+
+                if let Some(selected) = sources.selected_spot {
+                    selected.set(Some(spot));
+                }
+            }
+        }
+        PaletteResult::Coords(pos) => {
+            camera.fly_to(pos.map(f64::from), POINT_ZOOM);
+
+            if let Some(selected) = sources.selected {
+                selected.set(
+                    sources
+                        .terrs
+                        .read_untracked()
+                        .iter()
+                        .find(|(_, t)| t.location.contains(*pos))
+                        .map(|(n, _)| n.clone()),
+                );
+            }
+        }
+    }
+}
+
+/// Find the results matching the query ordered from the best match
+fn search(query: &str, sources: &PaletteSources) -> Vec<PaletteResult> {
+    let query = query.trim();
+
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut scored = Vec::new();
+
+    if let Some(coords) = parse_coords(query) {
+        scored.push((i32::MAX, PaletteResult::Coords(coords)));
+    }
+
+    for name in sources.terrs.read().keys() {
+        if let Some(score) = fuzzy_score(query, name) {
+            scored.push((score, PaletteResult::Territory(name.clone())));
+        }
+    }
+
+    let guilds: BTreeMap<_, _> = sources
+        .state
+        .read()
+        .values()
+        .filter(|s| s.guild != Guild::default())
+        .map(|s| (s.guild.prefix.clone(), s.guild.clone()))
+        .collect();
+
+    for guild in guilds.into_values() {
+        let score = fuzzy_score(query, &guild.name).max(fuzzy_score(query, &guild.prefix));
+
+        if let Some(score) = score {
+            scored.push((score, PaletteResult::Guild(guild)));
+        }
+    }
+
+    let spots = sources.spots.read();
+    for (i, mat) in spots.resources.iter().enumerate() {
+        if !spots.spots.iter().any(|s| s.resource == i) {
+            continue;
+        }
+
+        if let Some(score) = fuzzy_score(query, &mat.name) {
+            scored.push((score, PaletteResult::Material(i, mat.name.clone())));
+        }
+    }
+
+    // stable sort keeps the alphabetical order of equally good matches
+    scored.sort_by_key(|(s, _)| Reverse(*s));
+    scored.truncate(MAX_RESULTS);
+
+    scored.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Parse "x z" or "x, z" coordinates
+fn parse_coords(query: &str) -> Option<[i32; 2]> {
+    let mut parts = query
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|p| !p.is_empty());
+
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+
+    parts.next().is_none().then_some([x, z])
+}
+
+/// Score how well the query matches the text with higher scores being better matches
+///
+/// Every character of the query has to appear in the text in order. Matches at the start of words and consecutive
+/// matches score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let query = query.to_lowercase();
+    let text = text.to_lowercase();
+
+    if text == query {
+        return Some(1000);
+    }
+
+    let mut score = 0;
+    let mut query_chars = query.chars().peekable();
+    let mut prev: Option<char> = None;
+    let mut prev_matched = false;
+
+    for c in text.chars() {
+        let Some(&q) = query_chars.peek() else {
+            break;
+        };
+
+        if c == q {
+            query_chars.next();
+
+            score += 1;
+            if prev.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 8;
+            }
+            if prev_matched {
+                score += 4;
+            }
+
+            prev_matched = true;
+        } else {
+            prev_matched = false;
+        }
+
+        prev = Some(c);
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+
+    if text.starts_with(&query) {
+        score += 50;
+    }
+
+    // prefer shorter texts when the matches are otherwise equal
+    Some(score * 100 - text.len().min(99) as i32)
+}
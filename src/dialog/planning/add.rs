@@ -3,9 +3,12 @@ use std::sync::Arc;
 use leptos::prelude::*;
 use wynnmap_types::guild::Guild;
 
-use crate::dialog::{DialogCloseButton, Dialogs, planning::GuildFields};
+use crate::{
+    dialog::{DialogCloseButton, Dialogs, planning::GuildFields},
+    modes::planning::plan::Plan,
+};
 
-pub(super) fn add_guild(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoView {
+pub(super) fn add_guild(plan: Plan) -> impl IntoView {
     let tag = RwSignal::new(String::new());
     let name = RwSignal::new(String::new());
     let color = RwSignal::new(String::from("#FFFFFF"));
@@ -35,13 +38,11 @@ pub(super) fn add_guild(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoV
                     let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
                     move |_| {
                         // TODO: validate inputs
-                        guilds.update(|guilds| {
-                            guilds.push(ArcRwSignal::new(Guild {
-                                uuid: None,
-                                prefix: Arc::from(tag.get()),
-                                name: Arc::from(name.get()),
-                                color: Some(Arc::from(color.get())),
-                            }));
+                        plan.add_guild(Guild {
+                            uuid: None,
+                            prefix: Arc::from(tag.get()),
+                            name: Arc::from(name.get()),
+                            color: Some(Arc::from(color.get())),
                         });
 
                         dialogs.close();
@@ -0,0 +1,152 @@
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use jiff::tz::TimeZone;
+use leptos::prelude::*;
+use wynnmap_types::terr::Territory;
+
+use crate::{
+    dialog::{DialogCloseButton, Dialogs},
+    modes::planning::{plan::Plan, slots::PlanSlots},
+};
+
+/// Dialog for creating, opening and managing the plans saved in the browser
+pub fn plan_browser(
+    plan: Plan,
+    slots: PlanSlots,
+    live_terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let PlanSlots {
+        slots: list,
+        current,
+    } = slots;
+
+    // most recently saved plans first
+    let sorted = move || {
+        let mut list = list.get();
+        list.sort_by_key(|s| Reverse(s.updated));
+        list
+    };
+
+    let new_plan = move |_| {
+        let name = format!("Plan {}", list.read().len() + 1);
+        slots.create(plan, name, live_terrs.get());
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-3xl h-dvh md:max-h-150 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center">
+                    <h1 class="text-4xl">"Plans"</h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="p-2">
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={new_plan}>
+                    "New plan"
+                </button>
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="overflow-y-auto shrink relative">
+                <table class="table-auto w-full border-separate border-spacing-0">
+                    <thead class="sticky top-0 bg-neutral-900">
+                        <tr class="divide-x-1 divide-neutral-600">
+                            <th class="border-b-1 border-neutral-600">"Name"</th>
+                            <th class="border-b-1 border-neutral-600 w-0">"Saved"</th>
+                            <th class="border-b-1 border-neutral-600 w-0"></th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y-1 divide-neutral-600">
+                        <For
+                            each=sorted
+                            key=|slot| slot.clone()
+                            children=move |slot| {
+                                let id = slot.id;
+                                let is_current = move || current.get() == Some(id);
+                                let delete = {
+                                    let name = slot.name.clone();
+                                    move |_| {
+                                        let name = name.clone();
+                                        dialogs.add("delete_plan", move || delete_plan_dialog(plan, slots, id, name.clone()));
+                                    }
+                                };
+
+                                view! {
+                                    <tr class="divide-x-1 divide-neutral-600" class:bg-neutral-800=is_current>
+                                        <td class="border-b-1 border-neutral-600 p-2">
+                                            <input
+                                                type="text"
+                                                class="w-full p-1 rounded border-1 border-transparent hover:border-neutral-600"
+                                                value={slot.name.clone()}
+                                                on:change:target=move |ev| slots.rename(id, ev.target().value())
+                                            />
+                                        </td>
+                                        <td class="border-b-1 border-neutral-600 p-2 whitespace-nowrap">
+                                            {slot.updated.to_zoned(TimeZone::system()).strftime("%Y-%m-%d %H:%M").to_string()}
+                                        </td>
+                                        <td class="border-b-1 border-neutral-600 flex p-2 gap-1 size-fit">
+                                            <button
+                                                class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg disabled:text-neutral-600"
+                                                disabled=is_current
+                                                on:click={move |_| slots.open(plan, id)}
+                                            >
+                                                "Open"
+                                            </button>
+                                            <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| slots.duplicate(plan, id)}>
+                                                "Duplicate"
+                                            </button>
+                                            <div class="cursor-pointer p-1" title="Delete" on:click={delete}>
+                                                <icons::Trash size=24/>
+                                            </div>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
+
+/// Dialog confirming the deletion of a saved plan
+fn delete_plan_dialog(plan: Plan, slots: PlanSlots, id: u64, name: String) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let delete = move |_| {
+        slots.delete(plan, id);
+        dialogs.close();
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white flex flex-col">
+            <div class="flex justify-between p-2 items-center">
+                <h1 class="text-4xl">"Delete plan"</h1>
+
+                <DialogCloseButton />
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="p-2 flex flex-col gap-1">
+                <p>{format!("Delete \"{name}\"?")}</p>
+                <p class="text-neutral-400">"The plan is removed from this browser and can't be restored."</p>
+            </div>
+
+            <div class="flex justify-end gap-2 p-2">
+                <DialogCloseButton>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg">"Cancel"</button>
+                </DialogCloseButton>
+                <button on:click={delete} class="p-1 px-2 border-1 border-red-600 hover:bg-neutral-700 rounded-lg">"Delete"</button>
+            </div>
+        </div>
+    }
+}
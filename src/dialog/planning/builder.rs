@@ -0,0 +1,228 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::{prelude::*, task::spawn_local};
+use wynnmap_types::{
+    claim::{ClaimOptions, ResourceWeights, build_claim},
+    guild::Guild,
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    datasource,
+    dialog::{
+        DialogCloseButton, Dialogs,
+        planning::{GuildFields, formats::PlanningModeData},
+    },
+    modes::planning::{plan::Plan, slots::PlanSlots},
+};
+
+/// Dialog generating a claim around a chosen hq and opening it as a new plan
+pub fn claim_builder(
+    plan: Plan,
+    slots: PlanSlots,
+    live_terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let hq = RwSignal::new(String::new());
+    let size = RwSignal::new(String::from("20"));
+    let weights = [
+        ("Emeralds", RwSignal::new(String::from("1"))),
+        ("Ore", RwSignal::new(String::from("1"))),
+        ("Crops", RwSignal::new(String::from("1"))),
+        ("Fish", RwSignal::new(String::from("1"))),
+        ("Wood", RwSignal::new(String::from("1"))),
+    ];
+    let external_weight = RwSignal::new(String::from("3600"));
+    let avoid = RwSignal::new(String::new());
+
+    let tag = RwSignal::new(String::new());
+    let name = RwSignal::new(String::new());
+    let color = RwSignal::new(String::from("#FFFFFF"));
+
+    let error = RwSignal::new(None::<String>);
+
+    // the live map state used to find the territories of avoided guilds
+    let state = RwSignal::new(None::<BTreeMap<Arc<str>, TerrState>>);
+    spawn_local(async move {
+        match datasource::get_state().await {
+            Ok(s) => state.set(Some(s.terrs)),
+            Err(err) => error.set(Some(format!(
+                "Failed to load the map state so guilds can't be avoided: {err}"
+            ))),
+        }
+    });
+
+    // the territories of avoided guilds aren't known until the state has loaded
+    let waiting = move || !avoid.read().trim().is_empty() && state.read().is_none();
+
+    let generate = move |_| {
+        let terrs = live_terrs.get_untracked();
+        let hq: Arc<str> = Arc::from(hq.get_untracked().trim());
+
+        if !terrs.contains_key(&hq) {
+            error.set(Some(format!("No territory called \"{hq}\"")));
+            return;
+        }
+
+        let Ok(size) = size.get_untracked().trim().parse::<usize>() else {
+            error.set(Some(String::from(
+                "The territory count must be a whole number",
+            )));
+            return;
+        };
+
+        let num = |s: RwSignal<String>| s.get_untracked().trim().parse::<f64>().ok();
+        let (Some(emerald), Some(ore), Some(crop), Some(fish), Some(wood), Some(external_weight)) = (
+            num(weights[0].1),
+            num(weights[1].1),
+            num(weights[2].1),
+            num(weights[3].1),
+            num(weights[4].1),
+            num(external_weight),
+        ) else {
+            error.set(Some(String::from("The weights must be numbers")));
+            return;
+        };
+
+        let avoided: BTreeSet<String> = avoid
+            .get_untracked()
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect();
+
+        if !avoided.is_empty() && state.read_untracked().is_none() {
+            error.set(Some(String::from(
+                "The map state is still loading so the avoided territories aren't known yet",
+            )));
+            return;
+        }
+
+        let avoid = state
+            .read_untracked()
+            .iter()
+            .flatten()
+            .filter(|(_, s)| avoided.contains(&s.guild.prefix.to_lowercase()))
+            .map(|(n, _)| n.clone())
+            .collect();
+
+        let options = ClaimOptions {
+            hq: hq.clone(),
+            size,
+            weights: ResourceWeights {
+                emerald,
+                ore,
+                crop,
+                fish,
+                wood,
+            },
+            external_weight,
+            avoid,
+        };
+
+        if options.avoid.contains(&hq) {
+            error.set(Some(String::from(
+                "The hq is held by an avoided guild so no claim could be made",
+            )));
+            return;
+        }
+
+        let claim = build_claim(&terrs, &options);
+
+        if claim.is_empty() {
+            error.set(Some(String::from("No claim could be made around the hq")));
+            return;
+        }
+
+        let guild = ArcRwSignal::new(Guild {
+            uuid: None,
+            prefix: Arc::from(tag.get_untracked()),
+            name: Arc::from(name.get_untracked()),
+            color: Some(Arc::from(color.get_untracked())),
+        });
+
+        let data = PlanningModeData {
+            guilds: vec![ArcRwSignal::new(Guild::default()), guild.clone()],
+            owned_territories: claim.into_iter().map(|n| (n, guild.clone())).collect(),
+            hqs: BTreeSet::from([hq.clone()]),
+            ..Default::default()
+        };
+
+        slots.create(plan, format!("Claim around {hq}"), terrs);
+        plan.load(data);
+        plan.history.clear();
+
+        dialogs.close();
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-3xl h-dvh md:max-h-200 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center">
+                    <h1 class="text-4xl">"Claim builder"</h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="overflow-y-auto shrink">
+                <div class="p-2 flex flex-col">
+                    <label class="flex flex-col">
+                        "HQ territory:"
+                        <input type="text" list="claim-builder-terrs" placeholder="Territory" bind:value=hq class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+                        <datalist id="claim-builder-terrs">
+                            {move || live_terrs.read().keys().map(|n| view! { <option value={n.to_string()} /> }).collect::<Vec<_>>()}
+                        </datalist>
+                    </label>
+                    <label class="flex flex-col">
+                        "Territory count:"
+                        <input type="number" min="1" bind:value=size class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+                    </label>
+
+                    <h2 class="text-xl">"Weights"</h2>
+                    <div class="grid grid-cols-2 md:grid-cols-3">
+                        {weights.map(|(label, value)| view! {
+                            <label class="flex flex-col">
+                                {label}":"
+                                <input type="number" step="0.1" bind:value=value class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+                            </label>
+                        })}
+                        <label class="flex flex-col" title="Score of each external of the hq in resources per hour">
+                            "HQ externals:"
+                            <input type="number" bind:value=external_weight class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+                        </label>
+                    </div>
+
+                    <label class="flex flex-col">
+                        "Avoid territories held by (comma separated tags):"
+                        <input type="text" placeholder="Tags" bind:value=avoid class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+                    </label>
+                    <Show when=waiting>
+                        <p class="text-neutral-400 mx-2">"Loading the map state..."</p>
+                    </Show>
+                </div>
+
+                <hr class="border-neutral-600" />
+
+                <h2 class="text-xl p-2">"Claiming guild"</h2>
+                <GuildFields tag=tag name=name color=color />
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="p-2 flex justify-between items-center">
+                <p class="text-red-500 px-2">{move || error.get()}</p>
+
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700 disabled:text-neutral-600" disabled=waiting on:click={generate}>
+                    "Generate plan"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,85 @@
+use leptos::prelude::*;
+use wynnmap_types::resources::BaseResGen;
+
+use crate::{dialog::DialogCloseButton, modes::planning::compare::GuildDiff};
+
+/// Dialog summarising the territories and resources each guild gains or loses in the plan
+pub fn compare_summary(diffs: Signal<Vec<GuildDiff>>) -> impl IntoView {
+    // gained and lost amounts of a single resource
+    let res_cell = |gained: i32, lost: i32| {
+        view! {
+            <td class="border-b-1 border-neutral-600 p-2 whitespace-nowrap text-right">
+                <span class="text-green-500">"+"{gained}</span>
+                " "
+                <span class="text-red-500">"-"{lost}</span>
+            </td>
+        }
+    };
+
+    let resources = move |gained: BaseResGen, lost: BaseResGen| {
+        view! {
+            {res_cell(gained.emerald, lost.emerald)}
+            {res_cell(gained.ore, lost.ore)}
+            {res_cell(gained.crop, lost.crop)}
+            {res_cell(gained.fish, lost.fish)}
+            {res_cell(gained.wood, lost.wood)}
+        }
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-5xl h-dvh md:max-h-150 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center">
+                    <h1 class="text-4xl">"Plan comparison"</h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="overflow-auto shrink relative">
+                <table class="table-auto w-full border-separate border-spacing-0">
+                    <thead class="sticky top-0 bg-neutral-900">
+                        <tr class="divide-x-1 divide-neutral-600">
+                            <th class="border-b-1 border-neutral-600">"Guild"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Before"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"After"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Gained"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Lost"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Emeralds"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Ore"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Crops"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Fish"</th>
+                            <th class="border-b-1 border-neutral-600 p-1">"Wood"</th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y-1 divide-neutral-600">
+                        <For
+                            each=move || diffs.get()
+                            key=|diff| (diff.guild.clone(), diff.before, diff.after, diff.gained, diff.lost)
+                            children=move |diff| {
+                                view! {
+                                    <tr class="divide-x-1 divide-neutral-600">
+                                        <td class="border-b-1 border-neutral-600 p-2">
+                                            {diff.guild.name} " ["{diff.guild.prefix.to_string()}"]"
+                                        </td>
+                                        <td class="border-b-1 border-neutral-600 p-2 text-right">{diff.before}</td>
+                                        <td class="border-b-1 border-neutral-600 p-2 text-right">{diff.after}</td>
+                                        <td class="border-b-1 border-neutral-600 p-2 text-right text-green-500">"+"{diff.gained}</td>
+                                        <td class="border-b-1 border-neutral-600 p-2 text-right text-red-500">"-"{diff.lost}</td>
+                                        {resources(diff.res_gained, diff.res_lost)}
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+
+                <Show when={move || diffs.read().is_empty()}>
+                    <p class="p-2 text-neutral-400">"No guilds own territories in either plan."</p>
+                </Show>
+            </div>
+        </div>
+    }
+}
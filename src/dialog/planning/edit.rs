@@ -1,12 +1,19 @@
 use std::sync::Arc;
 
 use leptos::prelude::*;
-use wynnmap_types::guild::Guild;
 
-use crate::dialog::{DialogCloseButton, Dialogs, planning::GuildFields};
+use crate::{
+    dialog::{DialogCloseButton, Dialogs, planning::GuildFields},
+    modes::planning::plan::Plan,
+};
 
-pub(super) fn edit_guild(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>, n: usize) -> impl IntoView {
-    let guild = guilds.read_untracked().get(n).cloned().unwrap_or_default();
+pub(super) fn edit_guild(plan: Plan, n: usize) -> impl IntoView {
+    let guild = plan
+        .guilds
+        .read_untracked()
+        .get(n)
+        .cloned()
+        .unwrap_or_default();
 
     let tag = RwSignal::new(guild.get_untracked().prefix.to_string());
     let name = RwSignal::new(guild.get_untracked().name.to_string());
@@ -37,14 +44,10 @@ pub(super) fn edit_guild(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>, n: usize) ->
                     let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
                     move |_| {
                         // TODO: validate inputs
-                        guilds.update(|guilds| {
-                            if let Some(guild) = guilds.get_mut(n) {
-                                guild.update(|guild| {
-                                    guild.prefix = Arc::from(tag.get());
-                                    guild.name = Arc::from(name.get());
-                                    guild.color = Some(Arc::from(color.get()));
-                                });
-                            }
+                        plan.edit_guild(n, |guild| {
+                            guild.prefix = Arc::from(tag.get());
+                            guild.name = Arc::from(name.get());
+                            guild.color = Some(Arc::from(color.get()));
                         });
 
                         dialogs.close();
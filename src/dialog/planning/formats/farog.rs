@@ -0,0 +1,239 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::prelude::{ArcRwSignal, GetUntracked};
+use serde::{Deserialize, Serialize};
+use wynnmap_types::{
+    guild::Guild,
+    tower::{MAX_BONUS, TowerUpgrades},
+};
+
+use crate::dialog::planning::formats::{DataConvert, FileConvert, PlanningModeData};
+
+/// Name of the tower aura bonus in the bonus list of a territory
+const AURA_BONUS: &str = "towerAura";
+/// Name of the tower volley bonus in the bonus list of a territory
+const VOLLEY_BONUS: &str = "towerVolley";
+
+/// Base structure for the fa-rog.github.io/economy save file format
+///
+/// The save file is a json object containing the guild list and the configuration of each territory keyed by the
+/// territory name. Unknown fields are ignored and missing territory fields use their defaults so that older and newer
+/// saves can still be loaded.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Farog {
+    guilds: Vec<FGuild>,
+    territories: BTreeMap<String, FTerritory>,
+}
+
+impl DataConvert for Farog {
+    fn from_data(data: &PlanningModeData) -> Self {
+        // the first guild is always the "nobody" guild which is represented by a missing owner
+        let guilds = data
+            .guilds
+            .iter()
+            .skip(1)
+            .map(|g| g.get_untracked().into())
+            .collect();
+
+        let mut territories = BTreeMap::new();
+
+        for name in data.territories.keys() {
+            let owner = data.owner_idx(name);
+
+            let upgrades = data.upgrades.get(name).copied().unwrap_or_default();
+            let bonuses = [(AURA_BONUS, upgrades.aura), (VOLLEY_BONUS, upgrades.volley)]
+                .into_iter()
+                .filter(|(_, level)| *level != 0)
+                .map(|(name, level)| (name.to_string(), u32::from(level)))
+                .collect();
+
+            territories.insert(
+                name.to_string(),
+                FTerritory {
+                    guild: (owner != 0)
+                        .then(|| data.guilds[owner].get_untracked().prefix.to_string()),
+                    hq: owner != 0 && data.hqs.contains(name),
+                    upgrades: upgrades.into(),
+                    bonuses,
+                },
+            );
+        }
+
+        Self {
+            guilds,
+            territories,
+        }
+    }
+
+    fn to_data(self) -> PlanningModeData {
+        // first guild will always be [None]
+        let mut guilds = vec![ArcRwSignal::new(Guild::default())];
+
+        for guild in self.guilds {
+            guilds.push(ArcRwSignal::new(guild.into()));
+        }
+
+        let mut owned = BTreeMap::new();
+        let mut hqs = BTreeSet::new();
+        let mut upgrades = BTreeMap::new();
+
+        for (name, terr) in self.territories {
+            let name: Arc<str> = Arc::from(name);
+
+            let guildref = terr.guild.and_then(|tag| {
+                guilds
+                    .iter()
+                    .skip(1)
+                    .find(|g| *g.get_untracked().prefix == tag)
+                    .cloned()
+            });
+
+            let bonus = |name| {
+                terr.bonuses
+                    .get(name)
+                    .map_or(0, |l| (*l).min(u32::from(MAX_BONUS)) as u8)
+            };
+
+            let upgrade = TowerUpgrades {
+                aura: bonus(AURA_BONUS),
+                volley: bonus(VOLLEY_BONUS),
+                ..terr.upgrades.into()
+            };
+            if !upgrade.is_default() {
+                upgrades.insert(name.clone(), upgrade);
+            }
+
+            if let Some(guild) = guildref {
+                if terr.hq {
+                    hqs.insert(name.clone());
+                }
+
+                owned.insert(name, guild);
+            } else {
+                owned.insert(name, guilds[0].clone());
+            }
+        }
+
+        PlanningModeData {
+            guilds,
+            owned_territories: owned,
+            hqs,
+            upgrades,
+            ..Default::default()
+        }
+    }
+}
+
+impl FileConvert for Farog {
+    fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).expect("Serialization should not fail")
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, super::FileConvertError>
+    where
+        Self: Sized,
+    {
+        let data: Self = serde_json::from_slice(bytes)?;
+
+        // any json object with the two lists would parse so make sure it contains a plan
+        if data.territories.is_empty() {
+            return Err(super::FileConvertError::NoTerritories);
+        }
+
+        // territories of guilds missing from the guild list would silently be given to nobody
+        for (name, terr) in &data.territories {
+            if let Some(tag) = &terr.guild
+                && !data.guilds.iter().any(|g| g.tag == *tag)
+            {
+                return Err(super::FileConvertError::UnknownGuild(
+                    name.clone(),
+                    tag.clone(),
+                ));
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct FGuild {
+    name: String,
+    tag: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+impl From<Guild> for FGuild {
+    fn from(value: Guild) -> Self {
+        Self {
+            color: Some(value.hex_color()),
+            name: value.name.to_string(),
+            tag: value.prefix.to_string(),
+        }
+    }
+}
+
+impl From<FGuild> for Guild {
+    fn from(value: FGuild) -> Self {
+        Self {
+            uuid: None,
+            name: Arc::from(value.name),
+            prefix: Arc::from(value.tag),
+            color: value.color.map(Arc::from),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct FTerritory {
+    /// Tag of the owning guild if any
+    #[serde(default)]
+    guild: Option<String>,
+    #[serde(default)]
+    hq: bool,
+    #[serde(default)]
+    upgrades: FUpgrades,
+    /// Levels of the tower bonuses by name, only the aura and volley bonuses are used
+    #[serde(default)]
+    bonuses: BTreeMap<String, u32>,
+}
+
+/// Tower upgrade levels
+#[derive(Debug, Deserialize, Serialize, Default, Clone, Copy)]
+struct FUpgrades {
+    #[serde(default)]
+    damage: u8,
+    #[serde(default)]
+    attack: u8,
+    #[serde(default)]
+    health: u8,
+    #[serde(default)]
+    defence: u8,
+}
+
+impl From<TowerUpgrades> for FUpgrades {
+    fn from(value: TowerUpgrades) -> Self {
+        Self {
+            damage: value.damage,
+            attack: value.attack,
+            health: value.health,
+            defence: value.defence,
+        }
+    }
+}
+
+impl From<FUpgrades> for TowerUpgrades {
+    fn from(value: FUpgrades) -> Self {
+        Self {
+            damage: value.damage,
+            attack: value.attack,
+            health: value.health,
+            defence: value.defence,
+            ..Default::default()
+        }
+    }
+}
@@ -1,25 +1,49 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use leptos::prelude::ArcRwSignal;
 use thiserror::Error;
-use wynnmap_types::{guild::Guild, terr::Territory};
+use wynnmap_types::{guild::Guild, terr::Territory, tower::TowerUpgrades};
 
+pub mod farog;
 pub mod rueaes;
 pub mod urlshare;
 pub mod wynnmap;
 
 /// The internal representation of the planning mode data variables
+#[derive(Clone, Default)]
 pub struct PlanningModeData {
+    /// The territories of the plan
+    ///
+    /// This is empty if the format doesn't store territories in which case the current territories are used.
+    pub territories: BTreeMap<Arc<str>, Territory>,
     pub guilds: Vec<ArcRwSignal<Guild>>,
     pub owned_territories: BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
+    /// Names of the territories which are guild hqs
+    pub hqs: BTreeSet<Arc<str>>,
+    /// Notes written on territories
+    pub notes: BTreeMap<Arc<str>, String>,
+    /// Tower upgrades of territories
+    pub upgrades: BTreeMap<Arc<str>, TowerUpgrades>,
+}
+
+impl PlanningModeData {
+    /// Find the index of the owner of a territory in the guild list defaulting to the first guild
+    pub fn owner_idx(&self, terr: &str) -> usize {
+        self.owned_territories.get(terr).map_or(0, |own| {
+            self.guilds
+                .iter()
+                .enumerate()
+                .find(|(_, g)| *g == own)
+                .map_or(0, |(i, _)| i)
+        })
+    }
 }
 
 pub trait DataConvert {
-    fn from_data(
-        terrs: &BTreeMap<Arc<str>, Territory>,
-        guilds: &[ArcRwSignal<Guild>],
-        owned: &BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
-    ) -> Self;
+    fn from_data(data: &PlanningModeData) -> Self;
 
     fn to_data(self) -> PlanningModeData;
 }
@@ -38,4 +62,12 @@ pub trait FileConvert {
 pub enum FileConvertError {
     #[error("{0}")]
     SerdeJson(#[from] serde_json::Error),
+    #[error("The file doesn't contain any territories")]
+    NoTerritories,
+    #[error("The territory {0} is owned by the unknown guild [{1}]")]
+    UnknownGuild(String, String),
+    #[error("The file doesn't contain any guilds")]
+    NoGuilds,
+    #[error("The hq {0} of [{1}] isn't a territory of the guild")]
+    InvalidHq(String, String),
 }
@@ -6,7 +6,7 @@ use std::{
 use jiff::Timestamp;
 use leptos::prelude::{ArcRwSignal, GetUntracked};
 use serde::{Deserialize, Serialize};
-use wynnmap_types::{Region, guild::Guild, terr::Territory};
+use wynnmap_types::{Region, guild::Guild};
 
 use crate::dialog::planning::formats::{DataConvert, FileConvert, PlanningModeData};
 
@@ -33,20 +33,17 @@ pub struct RueaES {
 }
 
 impl DataConvert for RueaES {
-    fn from_data(
-        terrs: &BTreeMap<Arc<str>, Territory>,
-        guilds: &[ArcRwSignal<Guild>],
-        owned: &BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
-    ) -> Self {
-        let guilds2 = guilds
+    fn from_data(data: &PlanningModeData) -> Self {
+        let guilds2 = data
+            .guilds
             .iter()
             .map(|g| g.get_untracked().into())
             .collect::<Vec<RGuild>>();
 
         let mut terrs2 = Vec::new();
 
-        for (name, terr) in terrs {
-            let owner = owned.get(name).unwrap_or_else(|| guilds.first().unwrap());
+        for (name, terr) in &data.territories {
+            let owner = &data.guilds[data.owner_idx(name)];
 
             terrs2.push(RTerritory {
                 name: name.to_string(),
@@ -109,6 +106,7 @@ impl DataConvert for RueaES {
         PlanningModeData {
             guilds,
             owned_territories: terrs,
+            ..Default::default()
         }
     }
 }
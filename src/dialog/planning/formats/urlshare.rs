@@ -1,148 +1,254 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use bitcode::{Decode, Encode};
-use leptos::{
-    leptos_dom::logging::console_log,
-    prelude::{ArcRwSignal, GetUntracked},
-};
+use leptos::prelude::{ArcRwSignal, GetUntracked};
 use thiserror::Error;
 use wynnmap_types::{guild::Guild, terr::Territory};
 
-#[derive(Debug, Clone, Encode, Decode)]
+use crate::dialog::planning::formats::PlanningModeData;
+
+/// Prefix of version 2 share strings
+///
+/// Version 1 share strings are plain base64 which never contains a `.`.
+const V2_PREFIX: &str = "v2.";
+
+#[derive(Debug, Clone)]
 pub enum ShareUrlData {
-    V1 {
-        terrhash: u32,
-        guilds: Vec<V1Guild>,
-        territories: Vec<usize>,
-    },
+    V1(V1Share),
+    V2(V2Share),
+}
+
+/// First version of the share format which stores the owners by the position of the territory in the sorted
+/// territory list
+///
+/// This encodes identically to the original single variant enum so old share urls keep working.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct V1Share {
+    terrhash: u32,
+    guilds: Vec<V1Guild>,
+    territories: Vec<usize>,
+}
+
+/// Second version of the share format which identifies territories by a hash of their name
+///
+/// Territories which are added, removed or renamed only affect themselves instead of the whole share url.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct V2Share {
+    guilds: Vec<V2Guild>,
+}
+
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct V2Guild {
+    guild: V1Guild,
+    /// Name hash of the hq territory
+    hq: Option<u32>,
+    /// Name hashes of the owned territories
+    territories: Vec<u32>,
+}
+
+/// Differences between the territories of a share url and the current territories
+#[derive(Debug, Clone, Default)]
+pub struct ShareUrlMismatch {
+    /// The territory list has changed since the creation of a version 1 share url
+    pub terrs_changed: bool,
+    /// Guild prefix and number of territories of each guild which no longer exist
+    pub missing: Vec<(String, usize)>,
+}
+
+impl ShareUrlMismatch {
+    pub fn is_empty(&self) -> bool {
+        !self.terrs_changed && self.missing.is_empty()
+    }
 }
 
 impl ShareUrlData {
     pub fn from_data(
-        terrs: &BTreeMap<Arc<str>, Territory>,
         guilds: &[ArcRwSignal<Guild>],
         owned: &BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
+        hqs: &BTreeSet<Arc<str>>,
     ) -> Self {
-        let mut newguilds: Vec<V1Guild> = Vec::new();
+        let mut newguilds = Vec::new();
 
+        // the first guild is always the "nobody" guild which doesn't need to be stored
         for guild in guilds.iter().skip(1) {
-            newguilds.push(guild.get_untracked().into());
-        }
-
-        let mut terrnames = terrs.keys().map(Clone::clone).collect::<Vec<_>>();
-
-        // sort the terr names
-        terrnames.sort();
-
-        // generate the terr name hash to check if terrs have updated
-        let mut hasher = crc32fast::Hasher::new();
-        for t in &terrnames {
-            hasher.update(t.as_bytes());
-        }
-        let terrhash = hasher.finalize();
-
-        let mut owneds = Vec::new();
-
-        for territory in terrnames {
-            if let Some(owner) = owned.get(&territory) {
-                // figure out the index to the guilds array
-                let idx = guilds
-                    .iter()
-                    .enumerate()
-                    .find(|(_, g)| *g == owner)
-                    .map_or(0, |(i, _)| i);
+            let territories = owned
+                .iter()
+                .filter(|(_, g)| *g == guild)
+                .map(|(n, _)| name_hash(n))
+                .collect();
 
-                owneds.push(idx);
-            } else {
-                owneds.push(0);
-            }
+            let hq = owned
+                .iter()
+                .find(|(n, g)| *g == guild && hqs.contains(*n))
+                .map(|(n, _)| name_hash(n));
+
+            newguilds.push(V2Guild {
+                guild: guild.get_untracked().into(),
+                hq,
+                territories,
+            });
         }
 
-        Self::V1 {
-            terrhash,
-            guilds: newguilds,
-            territories: owneds,
-        }
+        Self::V2(V2Share { guilds: newguilds })
     }
 
     /// Turn the url share back into the planning mode data
-    pub fn into_data(
-        self,
-        terrs: &BTreeMap<Arc<str>, Territory>,
-    ) -> (
-        Vec<ArcRwSignal<Guild>>,
-        BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
-    ) {
-        let Self::V1 {
-            terrhash: _,
-            guilds,
-            territories,
-        } = self;
-
-        let mut guilds2 = vec![ArcRwSignal::new(Guild::default())];
-
-        for guild in guilds {
-            guilds2.push(ArcRwSignal::new(guild.into()));
+    ///
+    /// Territories which can't be matched are left unowned.
+    pub fn into_data(self, terrs: &BTreeMap<Arc<str>, Territory>) -> PlanningModeData {
+        match self {
+            Self::V1(V1Share {
+                terrhash: _,
+                guilds,
+                territories,
+            }) => {
+                let mut guilds2 = vec![ArcRwSignal::new(Guild::default())];
+
+                for guild in guilds {
+                    guilds2.push(ArcRwSignal::new(guild.into()));
+                }
+
+                let mut terrs2 = BTreeMap::new();
+
+                for (name, idx) in sorted_names(terrs).into_iter().zip(territories) {
+                    let guild = guilds2.get(idx).unwrap_or(&guilds2[0]);
+
+                    terrs2.insert(name, guild.clone());
+                }
+
+                PlanningModeData {
+                    guilds: guilds2,
+                    owned_territories: terrs2,
+                    ..Default::default()
+                }
+            }
+            Self::V2(V2Share { guilds }) => {
+                let by_hash = hashed_names(terrs);
+
+                let mut guilds2 = vec![ArcRwSignal::new(Guild::default())];
+                let mut owned: BTreeMap<Arc<str>, _> = terrs
+                    .keys()
+                    .map(|n| (n.clone(), guilds2[0].clone()))
+                    .collect();
+                let mut hqs = BTreeSet::new();
+
+                for guild in guilds {
+                    let guildref = ArcRwSignal::new(guild.guild.into());
+
+                    for name in guild.territories.iter().filter_map(|h| by_hash.get(h)) {
+                        owned.insert((*name).clone(), guildref.clone());
+                    }
+
+                    if let Some(name) = guild.hq.and_then(|h| by_hash.get(&h)) {
+                        hqs.insert((*name).clone());
+                    }
+
+                    guilds2.push(guildref);
+                }
+
+                PlanningModeData {
+                    guilds: guilds2,
+                    owned_territories: owned,
+                    hqs,
+                    ..Default::default()
+                }
+            }
         }
+    }
 
-        let mut terrnames = terrs.keys().map(Clone::clone).collect::<Vec<_>>();
-
-        // sort the terr names
-        terrnames.sort();
-
-        let mut terrs2 = BTreeMap::new();
-
-        for (name, idx) in terrnames.into_iter().zip(territories) {
-            let guild = guilds2
-                .iter()
-                .enumerate()
-                .find(|(i, _)| *i == idx)
-                .map_or_else(|| guilds2.first().unwrap(), |(_, g)| g);
+    /// Find the differences between the territories of the share url and the given territories
+    pub fn mismatch(&self, terrs: &BTreeMap<Arc<str>, Territory>) -> ShareUrlMismatch {
+        match self {
+            Self::V1(share) => {
+                // generate the terr name hash to check if terrs have updated
+                let mut hasher = crc32fast::Hasher::new();
+                for t in &sorted_names(terrs) {
+                    hasher.update(t.as_bytes());
+                }
+
+                ShareUrlMismatch {
+                    terrs_changed: share.terrhash != hasher.finalize(),
+                    ..Default::default()
+                }
+            }
+            Self::V2(share) => {
+                let by_hash = hashed_names(terrs);
 
-            terrs2.insert(name, guild.clone());
+                let missing = share
+                    .guilds
+                    .iter()
+                    .map(|g| {
+                        let count = g
+                            .territories
+                            .iter()
+                            .filter(|h| !by_hash.contains_key(*h))
+                            .count();
+
+                        (g.guild.prefix.clone(), count)
+                    })
+                    .filter(|(_, count)| *count > 0)
+                    .collect();
+
+                ShareUrlMismatch {
+                    missing,
+                    ..Default::default()
+                }
+            }
         }
-
-        (guilds2, terrs2)
     }
 
     pub fn decode_string(input: impl AsRef<str>) -> Result<Self, UrlShareDecodeError> {
-        let compressed_bytes = URL_SAFE_NO_PAD.decode(input.as_ref())?;
+        let input = input.as_ref();
 
-        let decompressed_bytes = zstd::decode_all(compressed_bytes.as_slice())?;
-
-        let data = bitcode::decode(&decompressed_bytes)?;
-
-        Ok(data)
+        if let Some(input) = input.strip_prefix(V2_PREFIX) {
+            Ok(Self::V2(bitcode::decode(&decode_bytes(input)?)?))
+        } else {
+            Ok(Self::V1(bitcode::decode(&decode_bytes(input)?)?))
+        }
     }
 
     pub fn encode_string(&self) -> String {
-        let bytes = bitcode::encode(self);
+        match self {
+            Self::V1(share) => encode_bytes(&bitcode::encode(share)),
+            Self::V2(share) => format!("{V2_PREFIX}{}", encode_bytes(&bitcode::encode(share))),
+        }
+    }
+}
 
-        let zstd = zstd::encode_all(bytes.as_slice(), 22).unwrap();
+fn decode_bytes(input: &str) -> Result<Vec<u8>, UrlShareDecodeError> {
+    let compressed_bytes = URL_SAFE_NO_PAD.decode(input)?;
 
-        URL_SAFE_NO_PAD.encode(zstd)
-    }
+    Ok(zstd::decode_all(compressed_bytes.as_slice())?)
+}
 
-    pub fn verify_terrhash(&self, terrs: &BTreeMap<Arc<str>, Territory>) -> bool {
-        let mut terrnames = terrs.keys().map(Clone::clone).collect::<Vec<_>>();
+fn encode_bytes(bytes: &[u8]) -> String {
+    let zstd = zstd::encode_all(bytes, 22).unwrap();
 
-        // sort the terr names
-        terrnames.sort();
+    URL_SAFE_NO_PAD.encode(zstd)
+}
 
-        // generate the terr name hash to check if terrs have updated
-        let mut hasher = crc32fast::Hasher::new();
-        for t in &terrnames {
-            hasher.update(t.as_bytes());
-        }
-        let terrhash2 = hasher.finalize();
+/// The territory names in sorted order as used by version 1 share urls
+fn sorted_names(terrs: &BTreeMap<Arc<str>, Territory>) -> Vec<Arc<str>> {
+    let mut terrnames = terrs.keys().cloned().collect::<Vec<_>>();
 
-        let Self::V1 { terrhash, .. } = self;
+    // sort the terr names
+    terrnames.sort();
 
-        console_log(&format!("th1: {terrhash} th2: {terrhash2}"));
+    terrnames
+}
 
-        *terrhash == terrhash2
-    }
+/// Hash of a territory name used to identify territories in version 2 share urls
+fn name_hash(name: &str) -> u32 {
+    crc32fast::hash(name.as_bytes())
+}
+
+/// Map the name hashes of the territories back to the names
+fn hashed_names(terrs: &BTreeMap<Arc<str>, Territory>) -> BTreeMap<u32, &Arc<str>> {
+    terrs.keys().map(|n| (name_hash(n), n)).collect()
 }
 
 #[derive(Debug, Clone, Encode, Decode)]
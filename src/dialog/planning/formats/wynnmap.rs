@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, sync::Arc};
 
 use leptos::prelude::{ArcRwSignal, GetUntracked};
 use serde::{Deserialize, Serialize};
-use wynnmap_types::{guild::Guild, terr::Territory};
+use wynnmap_types::{guild::Guild, terr::Territory, tower::TowerUpgrades};
 
 use crate::dialog::planning::formats::{DataConvert, FileConvert, PlanningModeData};
 
@@ -13,77 +13,132 @@ pub enum WynnmapData {
         guilds: Vec<V1Guild>,
         territories: BTreeMap<String, V1Territory>,
     },
+    /// Second version of the format which stores the full territories along with hqs, notes and tower upgrades
+    V2 {
+        guilds: Vec<V2Guild>,
+        territories: BTreeMap<String, V2Territory>,
+    },
 }
 
 impl DataConvert for WynnmapData {
-    fn from_data(
-        terrs: &BTreeMap<Arc<str>, Territory>,
-        guilds: &[ArcRwSignal<Guild>],
-        owned: &BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
-    ) -> Self {
+    fn from_data(data: &PlanningModeData) -> Self {
         let mut newguilds = Vec::new();
 
-        for guild in guilds {
-            newguilds.push(guild.get_untracked().into());
+        for guild in &data.guilds {
+            newguilds.push(V2Guild {
+                guild: guild.get_untracked().into(),
+                hq: None,
+            });
         }
 
         let mut newterrs = BTreeMap::new();
 
-        for terr in terrs {
-            let t = V1Territory {
-                location: terr.1.location,
-                owner: if let Some(own) = owned.get(terr.0) {
-                    guilds
-                        .iter()
-                        .enumerate()
-                        .find(|(_, g)| *g == own)
-                        .map_or(0, |(i, _)| i)
-                } else {
-                    0
-                },
+        for (name, terr) in &data.territories {
+            let owner = data.owner_idx(name);
+
+            if owner != 0 && data.hqs.contains(name) {
+                newguilds[owner].hq = Some(name.to_string());
+            }
+
+            let t = V2Territory {
+                territory: terr.clone(),
+                owner,
+                note: data.notes.get(name).cloned().unwrap_or_default(),
+                upgrades: data.upgrades.get(name).copied().unwrap_or_default(),
             };
 
-            newterrs.insert(terr.0.to_string(), t);
+            newterrs.insert(name.to_string(), t);
         }
 
-        Self::V1 {
+        Self::V2 {
             guilds: newguilds,
             territories: newterrs,
         }
     }
 
     fn to_data(self) -> super::PlanningModeData {
-        let Self::V1 {
-            guilds,
-            territories,
-        } = self;
-
-        let mut guilds2 = vec![];
-
-        for guild in guilds {
-            guilds2.push(ArcRwSignal::new(guild.into()));
-        }
-
-        let mut terrs2 = BTreeMap::new();
-
-        // TODO: load the entire territories from the file once that is implemented
-        for (name, terr) in territories {
-            let guild = guilds2
-                .iter()
-                .enumerate()
-                .find(|(i, _)| *i == terr.owner)
-                .map_or_else(|| guilds2.first().unwrap(), |(_, g)| g);
-
-            terrs2.insert(Arc::from(name), guild.clone());
-        }
-
-        PlanningModeData {
-            guilds: guilds2,
-            owned_territories: terrs2,
+        match self {
+            Self::V1 {
+                guilds,
+                territories,
+            } => {
+                let guilds2: Vec<_> = guilds
+                    .into_iter()
+                    .map(|g| ArcRwSignal::new(g.into()))
+                    .collect();
+
+                // V1 files only store the locations of territories so the current territories are used instead
+                let owners = territories.into_iter().map(|(n, t)| (n, t.owner));
+
+                PlanningModeData {
+                    owned_territories: owners_to_data(&guilds2, owners),
+                    guilds: guilds2,
+                    ..Default::default()
+                }
+            }
+            Self::V2 {
+                guilds,
+                territories,
+            } => {
+                let hqs = guilds
+                    .iter()
+                    .filter_map(|g| g.hq.as_deref())
+                    .map(Arc::from)
+                    .collect();
+
+                let guilds2: Vec<_> = guilds
+                    .into_iter()
+                    .map(|g| ArcRwSignal::new(g.guild.into()))
+                    .collect();
+
+                let mut terrs = BTreeMap::new();
+                let mut owners = BTreeMap::new();
+                let mut notes = BTreeMap::new();
+                let mut upgrades = BTreeMap::new();
+
+                for (name, terr) in territories {
+                    let name: Arc<str> = Arc::from(name);
+
+                    if !terr.note.is_empty() {
+                        notes.insert(name.clone(), terr.note);
+                    }
+
+                    if !terr.upgrades.is_default() {
+                        upgrades.insert(name.clone(), terr.upgrades);
+                    }
+
+                    owners.insert(name.clone(), terr.owner);
+                    terrs.insert(name, terr.territory);
+                }
+
+                PlanningModeData {
+                    territories: terrs,
+                    owned_territories: owners_to_data(&guilds2, owners),
+                    guilds: guilds2,
+                    hqs,
+                    notes,
+                    upgrades,
+                }
+            }
         }
     }
 }
 
+/// Convert guild indices into references to the guilds falling back to the first guild for invalid indices
+fn owners_to_data(
+    guilds: &[ArcRwSignal<Guild>],
+    owners: impl IntoIterator<Item = (impl Into<Arc<str>>, usize)>,
+) -> BTreeMap<Arc<str>, ArcRwSignal<Guild>> {
+    owners
+        .into_iter()
+        .filter_map(|(name, owner)| {
+            let guild = guilds.get(owner).or_else(|| guilds.first())?;
+
+            Some((name.into(), guild.clone()))
+        })
+        .collect()
+}
+
 impl FileConvert for WynnmapData {
     fn to_bytes(&self) -> Vec<u8> {
         serde_json::to_vec(&self).expect("Serialization should not fail")
@@ -93,7 +148,36 @@ impl FileConvert for WynnmapData {
     where
         Self: Sized,
     {
-        Ok(serde_json::from_slice(bytes)?)
+        let data: Self = serde_json::from_slice(bytes)?;
+
+        // the first guild is the nobody guild which every file has to contain
+        let guild_count = match &data {
+            Self::V1 { guilds, .. } => guilds.len(),
+            Self::V2 { guilds, .. } => guilds.len(),
+        };
+        if guild_count == 0 {
+            return Err(super::FileConvertError::NoGuilds);
+        }
+
+        // an hq has to be a territory of its own guild
+        if let Self::V2 {
+            guilds,
+            territories,
+        } = &data
+        {
+            for (idx, guild) in guilds.iter().enumerate() {
+                if let Some(hq) = &guild.hq
+                    && (idx == 0 || territories.get(hq).is_none_or(|t| t.owner != idx))
+                {
+                    return Err(super::FileConvertError::InvalidHq(
+                        hq.clone(),
+                        guild.guild.prefix.clone(),
+                    ));
+                }
+            }
+        }
+
+        Ok(data)
     }
 }
 
@@ -132,3 +216,23 @@ pub struct V1Territory {
     location: wynnmap_types::Region,
     owner: usize,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct V2Guild {
+    #[serde(flatten)]
+    guild: V1Guild,
+    /// Name of the hq territory of the guild
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hq: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct V2Territory {
+    #[serde(flatten)]
+    territory: Territory,
+    owner: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    note: String,
+    #[serde(default, skip_serializing_if = "TowerUpgrades::is_default")]
+    upgrades: TowerUpgrades,
+}
@@ -0,0 +1,149 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use leptos::{prelude::*, task::spawn_local};
+use wynnmap_types::guild::Guild;
+
+use crate::{
+    datasource,
+    dialog::{DialogCloseButton, Dialogs},
+    modes::planning::plan::Plan,
+};
+
+pub(super) fn import_guilds(plan: Plan) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let state = RwSignal::new(None);
+
+    spawn_local(async move {
+        state.set(Some(
+            datasource::get_state()
+                .await
+                .map(|s| s.terrs)
+                .map_err(|e| e.to_string()),
+        ));
+    });
+
+    let search = RwSignal::new(String::new());
+
+    // live guilds with their territory counts sorted by the number of territories
+    let live_guilds = move || {
+        let state = state.read();
+        let Some(Ok(state)) = state.as_ref() else {
+            return Vec::new();
+        };
+
+        let mut counts: BTreeMap<Arc<str>, (Guild, usize)> = BTreeMap::new();
+
+        for terr in state.values().filter(|s| s.guild != Guild::default()) {
+            counts
+                .entry(terr.guild.prefix.clone())
+                .or_insert_with(|| (terr.guild.clone(), 0))
+                .1 += 1;
+        }
+
+        let search = search.read().to_lowercase();
+
+        let mut guilds: Vec<_> = counts
+            .into_values()
+            .filter(|(g, _)| {
+                g.prefix.to_lowercase().contains(&search) || g.name.to_lowercase().contains(&search)
+            })
+            .collect();
+
+        guilds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.prefix.cmp(&b.0.prefix)));
+
+        guilds
+    };
+
+    let import_all = move |_| {
+        if let Some(Ok(state)) = &*state.read() {
+            plan.import_state(state);
+            dialogs.close();
+        }
+    };
+
+    let import_guild = move |prefix: Arc<str>| {
+        if let Some(Ok(state)) = &*state.read() {
+            plan.import_guild(state, &prefix);
+        }
+    };
+
+    let status = move || match &*state.read() {
+        None => {
+            Some(view! { <p class="p-2 text-neutral-400">"Loading map state..."</p> }.into_any())
+        }
+        Some(Err(err)) => Some(
+            view! {
+                <p class="p-2">"Failed to load the map state"</p>
+                <pre class="p-2 bg-neutral-800 rounded my-1">{err.clone()}</pre>
+            }
+            .into_any(),
+        ),
+        Some(Ok(_)) => None,
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-3xl h-dvh md:max-h-150 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center">
+                    <h1 class="text-4xl">"Import from map"</h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="p-2 flex flex-col">
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={import_all}>
+                    "Import the entire map"
+                </button>
+                <p class="px-2 text-neutral-400">"This replaces all guilds and territory owners of the current plan."</p>
+
+                <input type="text" placeholder="Search guilds" bind:value=search class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" />
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            {status}
+
+            <div class="overflow-y-auto shrink relative">
+                <table class="table-auto w-full border-separate border-spacing-0">
+                    <thead class="sticky top-0 bg-neutral-900">
+                        <tr class="divide-x-1 divide-neutral-600">
+                            <th class="border-b-1 border-neutral-600 w-0">"Tag"</th>
+                            <th class="border-b-1 border-neutral-600">"Name"</th>
+                            <th class="border-b-1 border-neutral-600 w-0">"Territories"</th>
+                            <th class="border-b-1 border-neutral-600 w-0"></th>
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y-1 divide-neutral-600">
+                        <For
+                            each=live_guilds
+                            key=|(guild, count)| (guild.prefix.clone(), *count)
+                            children=move |(guild, count)| {
+                                let prefix = guild.prefix.clone();
+
+                                view! {
+                                    <tr class="divide-x-1 divide-neutral-600">
+                                        <td class="border-b-1 border-neutral-600 p-2 font-mono size-fit">
+                                            <span class="pr-2" style:color={guild.hex_color()}>"⬤"</span>
+                                            "["{guild.prefix.clone()}"]"
+                                        </td>
+                                        <td class="border-b-1 border-neutral-600 p-2">{guild.name.clone()}</td>
+                                        <td class="border-b-1 border-neutral-600 p-2 size-fit">{count}</td>
+                                        <td class="border-b-1 border-neutral-600 p-2 size-fit">
+                                            <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| import_guild(prefix.clone())}>
+                                                "Import"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
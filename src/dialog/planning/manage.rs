@@ -1,12 +1,15 @@
 use leptos::prelude::*;
-use wynnmap_types::guild::Guild;
 
-use crate::dialog::{
-    DialogCloseButton, Dialogs,
-    planning::{add::add_guild, edit::edit_guild},
+use crate::{
+    dialog::{
+        DialogCloseButton, Dialogs,
+        planning::{add::add_guild, edit::edit_guild, import::import_guilds},
+    },
+    modes::planning::plan::Plan,
 };
 
-pub fn manage_guilds(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoView {
+pub fn manage_guilds(plan: Plan) -> impl IntoView {
+    let guilds = plan.guilds;
     let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
 
     view! {
@@ -24,23 +27,19 @@ pub fn manage_guilds(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoView
             <div class="p-2">
                 <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
                     move |_| {
-                        dialogs.add("add_guild", move || add_guild(guilds));
+                        dialogs.add("add_guild", move || add_guild(plan));
                     }
                 }>
                     "Add guild"
                 </button>
 
-                // TODO: allow importing guilds from the map/api
-                // <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
-                //     let owner = Owner::new();
-                //     move |_| {
-                //         owner.with(move || {
-                //             // show_dialog(dialog::planning::manage_terrs);
-                //         });
-                //     }
-                // }>
-                //     "Import guild"
-                // </button>
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
+                    move |_| {
+                        dialogs.add("import_guilds", move || import_guilds(plan));
+                    }
+                }>
+                    "Import from map"
+                </button>
             </div>
 
             <hr class="border-neutral-600" />
@@ -75,7 +74,7 @@ pub fn manage_guilds(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoView
                                                     let idx = idx.get();
                                                     move |_| {
                                                         if idx != 0 {
-                                                            dialogs.add("edit_guild", move || edit_guild(guilds, idx));
+                                                            dialogs.add("edit_guild", move || edit_guild(plan, idx));
                                                         }
                                                     }
                                                 }
@@ -86,9 +85,7 @@ pub fn manage_guilds(guilds: RwSignal<Vec<ArcRwSignal<Guild>>>) -> impl IntoView
                                             // delete button
                                             <div class="cursor-pointer" on:click={ move |_|
                                                     if idx.read() != 0 {
-                                                        guilds.update(|guilds| {
-                                                            guilds.remove(idx.get());
-                                                        });
+                                                        plan.remove_guild(idx.get());
                                                     }
                                                 }
                                             >
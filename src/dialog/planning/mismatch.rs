@@ -0,0 +1,56 @@
+use leptos::prelude::*;
+
+use crate::dialog::{
+    DialogCloseButton, Dialogs,
+    planning::formats::{PlanningModeData, urlshare::ShareUrlMismatch},
+};
+
+/// Dialog shown when the territories of a share url don't match the current territories
+pub fn share_mismatch_dialog(
+    data: PlanningModeData,
+    mismatch: ShareUrlMismatch,
+    load: Callback<PlanningModeData>,
+) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+
+    let load = move |_| {
+        load.run(data.clone());
+        dialogs.close();
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white flex flex-col">
+            <div class="flex justify-between p-2 items-center">
+                <h1 class="text-4xl">"Territory mismatch"</h1>
+
+                <DialogCloseButton />
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="p-2 flex flex-col gap-1">
+                <p>"Territories have changed since the creation of this share URL."</p>
+
+                {mismatch.terrs_changed.then(|| view! {
+                    <p class="text-neutral-400">"This URL was created with an older format so owners may end up on the wrong territories."</p>
+                })}
+
+                {(!mismatch.missing.is_empty()).then(|| view! {
+                    <p>"Some territories of this plan no longer exist and will not be loaded. Their names aren't stored in the URL, only how many each guild owned:"</p>
+                    <ul class="list-disc pl-6">
+                        {mismatch.missing.iter().map(|(prefix, count)| view! {
+                            <li>{format!("{count} territories of [{prefix}]")}</li>
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                })}
+            </div>
+
+            <div class="flex justify-end gap-2 p-2">
+                <DialogCloseButton>
+                    <button class="p-1 px-2 border-1 border-red-600 hover:bg-neutral-700 rounded-lg">"Cancel"</button>
+                </DialogCloseButton>
+                <button on:click={load} class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg">"Load anyway"</button>
+            </div>
+        </div>
+    }
+}
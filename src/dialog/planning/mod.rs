@@ -2,16 +2,30 @@ use leptos::prelude::*;
 
 /// Dialog to add a new guild
 mod add;
+/// Dialog for managing the plans saved in the browser
+mod browser;
+/// Dialog generating a claim around a chosen hq
+mod builder;
+/// Plan comparison summary dialog
+mod compare;
 /// Dialog to edit an existing guild
 mod edit;
 /// save formats used by the planning mode
 pub mod formats;
+/// Dialog to import guilds from the live map
+mod import;
 /// The whole manage guilds dialog
 mod manage;
+/// Share url territory mismatch dialog
+mod mismatch;
 /// save/import dialog
 mod save;
 
+pub use browser::plan_browser;
+pub use builder::claim_builder;
+pub use compare::compare_summary;
 pub use manage::manage_guilds;
+pub use mismatch::share_mismatch_dialog;
 pub use save::save_dialog;
 
 /// Component for the fields that are shared by add guild and edit guild dialogs
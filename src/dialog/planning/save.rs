@@ -1,16 +1,19 @@
-use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+use std::path::PathBuf;
 
-use leptos::prelude::*;
+use leptos::{prelude::*, task::spawn_local};
 use web_sys::{
     HtmlAnchorElement, HtmlInputElement,
     js_sys::{ArrayBuffer, Uint8Array},
     wasm_bindgen::{JsCast, JsValue, prelude::Closure},
 };
-use wynnmap_types::{guild::Guild, terr::Territory};
 
-use crate::dialog::{
-    DialogCloseButton,
-    planning::formats::{self, DataConvert, FileConvert, PlanningModeData},
+use crate::{
+    datasource,
+    dialog::{
+        DialogCloseButton,
+        planning::formats::{self, DataConvert, FileConvert, PlanningModeData},
+    },
+    modes::planning::plan::Plan,
 };
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -40,22 +43,18 @@ impl FileFormat {
     }
 }
 
-pub fn save_dialog(
-    terrs: Signal<BTreeMap<Arc<str>, Territory>>,
-    guilds: RwSignal<Vec<ArcRwSignal<Guild>>>,
-    owned: RwSignal<BTreeMap<Arc<str>, ArcRwSignal<Guild>>>,
-) -> impl IntoView {
+pub fn save_dialog(plan: Plan) -> impl IntoView {
+    let Plan {
+        guilds, owned, hqs, ..
+    } = plan;
+
     let filename = RwSignal::new(String::new());
     let fileformat = RwSignal::new(FileFormat::Wynnmap);
+    let encoded = Memo::new(move |_| {
+        formats::urlshare::ShareUrlData::from_data(&guilds.read(), &owned.read(), &hqs.read())
+            .encode_string()
+    });
     let sharestring = Memo::new(move |_| {
-        let data = formats::urlshare::ShareUrlData::from_data(
-            &terrs.read(),
-            &guilds.read(),
-            &owned.read(),
-        );
-
-        let encoded = data.encode_string();
-
         let location = window().location();
 
         let out = format!(
@@ -63,12 +62,43 @@ pub fn save_dialog(
             location.origin().unwrap_or_default(),
             location.pathname().unwrap_or_default(),
             location.search().unwrap_or_default(),
-            encoded
+            encoded.get()
         );
 
         out
     });
 
+    // short link to the plan stored on the server
+    let shortlink = RwSignal::new(None::<Result<String, String>>);
+    // plans expire by default so that forgotten links don't fill up the server
+    let expires = RwSignal::new(String::from("30"));
+
+    let create_shortlink = move |_| {
+        let expires_days = expires.get().parse().ok();
+        shortlink.set(None);
+
+        spawn_local(async move {
+            let link = datasource::store_plan(encoded.get_untracked(), expires_days)
+                .await
+                .map(|stored| {
+                    format!(
+                        "{}/plan/{}",
+                        window().location().origin().unwrap_or_default(),
+                        stored.id
+                    )
+                })
+                .map_err(|e| e.to_string());
+
+            shortlink.set(Some(link));
+        });
+    };
+
+    let copyshortlink = move |_| {
+        if let Some(Ok(link)) = shortlink.get() {
+            let _promise = window().navigator().clipboard().write_text(&link);
+        }
+    };
+
     let copystring = move |_| {
         let clipboard = window().navigator().clipboard();
 
@@ -85,18 +115,12 @@ pub fn save_dialog(
     };
 
     let downloadbtn = move |_| {
+        let data = plan.to_data();
+
         let bytes = match fileformat.get() {
-            FileFormat::Wynnmap => formats::wynnmap::WynnmapData::from_data(
-                &terrs.read(),
-                &guilds.read(),
-                &owned.read(),
-            )
-            .to_bytes(),
-            FileFormat::Farog => todo!(),
-            FileFormat::RueaES => {
-                formats::rueaes::RueaES::from_data(&terrs.read(), &guilds.read(), &owned.read())
-                    .to_bytes()
-            }
+            FileFormat::Wynnmap => formats::wynnmap::WynnmapData::from_data(&data).to_bytes(),
+            FileFormat::Farog => formats::farog::Farog::from_data(&data).to_bytes(),
+            FileFormat::RueaES => formats::rueaes::RueaES::from_data(&data).to_bytes(),
         };
 
         // let jsbytes = JsValue::from(bytes);
@@ -121,10 +145,7 @@ pub fn save_dialog(
 
     let file_load_err = RwSignal::new(None);
 
-    let load_data = move |data: PlanningModeData| {
-        guilds.set(data.guilds);
-        owned.set(data.owned_territories);
-    };
+    let load_data = move |data: PlanningModeData| plan.load(data);
 
     let loadfile = move |e: leptos::ev::Event| {
         // reset error
@@ -152,6 +173,9 @@ pub fn save_dialog(
                             "lz4" => {
                                 decode::<formats::rueaes::RueaES>(&bytes, load_data, file_load_err);
                             }
+                            "json" => {
+                                decode::<formats::farog::Farog>(&bytes, load_data, file_load_err);
+                            }
 
                             _ => file_load_err.set(Some(String::from("Unknown file extension"))),
                         }
@@ -193,6 +217,41 @@ pub fn save_dialog(
 
             <hr class="border-neutral-600" />
 
+            <div class="p-2">
+                <h2 class="text-xl">"Short link"</h2>
+
+                <div class="flex p-1 gap-2 items-center">
+                    <select class="border-1 border-neutral-600 p-1 px-2 rounded-lg" on:input:target={move |e| expires.set(e.target().value())}>
+                        <option value="1">"Expire after a day"</option>
+                        <option value="7">"Expire after a week"</option>
+                        <option value="30" selected>"Expire after a month"</option>
+                        <option value="">"Keep forever"</option>
+                    </select>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={create_shortlink}>
+                        "Create short link"
+                    </button>
+                </div>
+
+                {move || match shortlink.get() {
+                    Some(Ok(link)) => Some(view! {
+                        <div class="flex p-1">
+                            <input type="text" class="grow border-1 border-neutral-600 p-1 px-2 rounded-l-lg" value={link} readonly onfocus="this.select()"/>
+                            <button class="border-1 border-l-0 border-neutral-600 p-1 px-2 rounded-r-lg hover:bg-neutral-700 flex gap-1" on:click={copyshortlink}>
+                                "Copy"
+                                <icons::Clipboard size=24/>
+                            </button>
+                        </div>
+                    }.into_any()),
+                    Some(Err(err)) => Some(view! {
+                        <p>"Failed to create a short link"</p>
+                        <pre class="p-2 bg-neutral-800 rounded my-1">{err}</pre>
+                    }.into_any()),
+                    None => None,
+                }}
+            </div>
+
+            <hr class="border-neutral-600" />
+
             <div class="p-2">
                 <h2 class="text-xl">"Save to file"</h2>
 
@@ -200,7 +259,7 @@ pub fn save_dialog(
                     <p class="inline-block">"Format:"</p>
                     <select class="border-1 border-neutral-600 p-1 px-2 rounded-lg ml-1" on:input:target={move |e| formatselect(e.target().value()) }>
                         <option value=1 selected={move || *fileformat.read() == FileFormat::Wynnmap}>"Wynnmap"</option>
-                        // <option value=2 selected={move || *fileformat.read() == FileFormat::Farog}>"fa-rog's economy simulator"</option>
+                        <option value=2 selected={move || *fileformat.read() == FileFormat::Farog}>"fa-rog's economy simulator"</option>
                         <option value=3 selected={move || *fileformat.read() == FileFormat::RueaES}>"Ruea Economy Studio"</option>
                     </select>
                 </div>
@@ -216,6 +275,12 @@ pub fn save_dialog(
                     </span>
                     <button class="border-1 border-l-0 border-neutral-600 p-1 px-2 rounded-r-lg hover:bg-neutral-700" on:click={downloadbtn}>"Download"</button>
                 </div>
+
+                <Show when={move || *fileformat.read() == FileFormat::Farog}>
+                    <p class="text-neutral-400 mt-1">
+                        "Note: fa-rog's economy simulator doesn't store territory notes. They are not saved in this format."
+                    </p>
+                </Show>
             </div>
 
             <hr class="border-neutral-600" />
@@ -230,7 +295,7 @@ pub fn save_dialog(
                 </Show>
 
                 <p class="text-neutral-400">
-                    "Supported filetypes: Wynnmap, RueaES (lz4)"
+                    "Supported filetypes: Wynnmap, fa-rog's economy simulator (json), RueaES (lz4)"
                 </p>
             </div>
         </div>
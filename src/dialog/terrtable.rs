@@ -0,0 +1,319 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BTreeMap,
+    sync::Arc,
+};
+
+use jiff::Timestamp;
+use leptos::prelude::*;
+use wynnmap_types::{
+    guild::Guild,
+    resources::ResourceValues,
+    terr::{TerrState, Territory},
+    tier::WynnTier,
+};
+
+use crate::{
+    dialog::{DialogCloseButton, Dialogs},
+    sectimer::SecondTimer,
+    util::fmt_time_short,
+    wynnmap::context::MapPosition,
+};
+
+/// Names of the resources in the order of the resource columns
+const RESOURCES: [&str; 5] = ["emeralds", "ore", "wood", "fish", "crops"];
+
+/// Column the table is sorted by
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Name,
+    Owner,
+    TimeHeld,
+    Treasury,
+    Defences,
+    Hq,
+    /// Generation of the resource with the given index in [`RESOURCES`]
+    Generation(usize),
+    /// Stored amount of the resource with the given index in [`RESOURCES`]
+    Stored(usize),
+}
+
+impl SortKey {
+    /// Whether the column is sorted from the highest value when it is first chosen
+    const fn descending_first(self) -> bool {
+        !matches!(self, Self::Name | Self::Owner)
+    }
+
+    fn compare(self, a: &Row, b: &Row) -> Ordering {
+        match self {
+            Self::Name => a.name.cmp(&b.name),
+            Self::Owner => a.guild.name.cmp(&b.guild.name),
+            // territories acquired earlier have been held longer
+            Self::TimeHeld => a.acquired.map(Reverse).cmp(&b.acquired.map(Reverse)),
+            Self::Treasury => a.treasury.cmp(&b.treasury),
+            Self::Defences => a.defences.cmp(&b.defences),
+            Self::Hq => a.hq.cmp(&b.hq),
+            Self::Generation(i) => a.resources[i].generation.cmp(&b.resources[i].generation),
+            Self::Stored(i) => a.resources[i].stored.cmp(&b.resources[i].stored),
+        }
+    }
+}
+
+/// A territory joined with its state
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Row {
+    name: Arc<str>,
+    guild: Guild,
+    acquired: Option<Timestamp>,
+    treasury: WynnTier,
+    defences: WynnTier,
+    hq: bool,
+    /// Values of the resources in the order of [`RESOURCES`]
+    resources: [ResourceValues; 5],
+    /// Whether the territory produces each resource
+    produces: [bool; 5],
+}
+
+impl Row {
+    fn new(name: Arc<str>, terr: &Territory, state: TerrState) -> Self {
+        let g = terr.generates;
+        let r = state.resources;
+
+        Self {
+            name,
+            guild: state.guild,
+            acquired: state.acquired,
+            treasury: state.treasury,
+            defences: state.defences,
+            hq: state.hq,
+            resources: [r.emerald, r.ore, r.wood, r.fish, r.crop],
+            produces: [
+                g.has_emerald(),
+                g.has_ore(),
+                g.has_wood(),
+                g.has_fish(),
+                g.has_crop(),
+            ],
+        }
+    }
+
+    fn to_csv(&self, now: Timestamp) -> String {
+        let mut fields = vec![
+            csv_field(&self.name),
+            csv_field(&self.guild.name),
+            csv_field(&self.guild.prefix),
+            self.acquired.map(|a| a.to_string()).unwrap_or_default(),
+            self.acquired
+                .map(|a| now.duration_since(a).as_secs().to_string())
+                .unwrap_or_default(),
+            self.treasury.to_string(),
+            self.defences.to_string(),
+            self.hq.to_string(),
+        ];
+
+        for res in &self.resources {
+            fields.push(res.generation.to_string());
+            fields.push(res.stored.to_string());
+            fields.push(res.limit.to_string());
+        }
+
+        fields.join(",")
+    }
+}
+
+/// Quote a csv field if needed
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Dialog listing every territory with its state
+pub fn terr_table(
+    terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    selected: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
+    let camera = expect_context::<MapPosition>();
+    let SecondTimer(now) = expect_context::<SecondTimer>();
+
+    let sort = RwSignal::new((SortKey::Name, false));
+    let guild_filter = RwSignal::new(String::new());
+    let res_filter = RwSignal::new(None::<usize>);
+
+    let rows = Memo::new(move |_| {
+        let state = state.read();
+        let guild_filter = guild_filter.read().trim().to_lowercase();
+        let res_filter = res_filter.get();
+
+        let mut rows: Vec<_> = terrs
+            .read()
+            .iter()
+            .map(|(n, t)| Row::new(n.clone(), t, state.get(n).cloned().unwrap_or_default()))
+            .filter(|r| {
+                guild_filter.is_empty()
+                    || r.guild.prefix.to_lowercase() == guild_filter
+                    || r.guild.name.to_lowercase().contains(&guild_filter)
+            })
+            .filter(|r| res_filter.is_none_or(|i| r.produces[i]))
+            .collect();
+
+        let (key, descending) = sort.get();
+        rows.sort_by(|a, b| {
+            let ord = key.compare(a, b);
+            if descending { ord.reverse() } else { ord }
+        });
+
+        rows
+    });
+
+    let sort_by = move |key: SortKey| {
+        sort.update(|(k, desc)| {
+            if *k == key {
+                *desc = !*desc;
+            } else {
+                *k = key;
+                *desc = key.descending_first();
+            }
+        });
+    };
+
+    let copy_csv = move |_| {
+        let now = now.get_untracked();
+
+        let mut lines = vec![String::from(
+            "Territory,Guild,Tag,Acquired,Held seconds,Treasury,Defences,HQ",
+        )];
+        for res in RESOURCES {
+            lines[0].push_str(&format!(",{res} generation,{res} stored,{res} limit"));
+        }
+        lines.extend(rows.read_untracked().iter().map(|r| r.to_csv(now)));
+
+        let _promise = window()
+            .navigator()
+            .clipboard()
+            .write_text(&lines.join("\n"));
+    };
+
+    let fly_to = move |name: Arc<str>| {
+        if let Some(terr) = terrs.read_untracked().get(&name) {
+            camera.fit_region(terr.location);
+        }
+
+        selected.set(Some(name));
+        dialogs.close();
+    };
+
+    // sortable column header
+    let header = move |label: String, key: SortKey| {
+        let arrow = move || match sort.get() {
+            (k, false) if k == key => " ▲",
+            (k, true) if k == key => " ▼",
+            _ => "",
+        };
+
+        view! {
+            <th
+                class="border-b-1 border-neutral-600 p-1 cursor-pointer whitespace-nowrap hover:bg-neutral-700"
+                on:click=move |_| sort_by(key)
+            >
+                {label}{arrow}
+            </th>
+        }
+    };
+
+    view! {
+        <div class="bg-neutral-900 md:rounded-xl text-white w-screen max-w-7xl h-dvh md:max-h-200 flex flex-col">
+            <div>
+                <div class="flex justify-between p-2 items-center">
+                    <h1 class="text-4xl">"Territories"</h1>
+
+                    <DialogCloseButton />
+                </div>
+
+                <hr class="border-neutral-600" />
+            </div>
+
+            <div class="flex flex-wrap items-center gap-2 p-2">
+                <input
+                    type="text"
+                    class="p-1 rounded border-1 border-neutral-600"
+                    placeholder="Guild name or tag"
+                    bind:value=guild_filter
+                />
+                <select
+                    class="p-1 rounded border-1 border-neutral-600 bg-neutral-900"
+                    on:change:target=move |ev| res_filter.set(ev.target().value().parse().ok())
+                >
+                    <option value="">"Any resource"</option>
+                    {RESOURCES.iter().enumerate().map(|(i, res)| view! {
+                        <option value={i.to_string()}>{*res}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <span class="text-neutral-400">{move || rows.read().len()}" territories"</span>
+                <button
+                    class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg ml-auto"
+                    on:click=copy_csv
+                >
+                    "Copy CSV"
+                </button>
+            </div>
+
+            <hr class="border-neutral-600" />
+
+            <div class="overflow-auto shrink relative">
+                <table class="table-auto w-full border-separate border-spacing-0">
+                    <thead class="sticky top-0 bg-neutral-900">
+                        <tr class="divide-x-1 divide-neutral-600">
+                            {header(String::from("Territory"), SortKey::Name)}
+                            {header(String::from("Owner"), SortKey::Owner)}
+                            {header(String::from("Time held"), SortKey::TimeHeld)}
+                            {header(String::from("Treasury"), SortKey::Treasury)}
+                            {header(String::from("Defences"), SortKey::Defences)}
+                            {header(String::from("HQ"), SortKey::Hq)}
+                            {RESOURCES.iter().enumerate().map(|(i, res)| view! {
+                                {header(format!("{res}/h"), SortKey::Generation(i))}
+                                {header(format!("{res} stored"), SortKey::Stored(i))}
+                            }).collect::<Vec<_>>()}
+                        </tr>
+                    </thead>
+                    <tbody>
+                        <For
+                            each=move || rows.get()
+                            key=|row| row.clone()
+                            children=move |row| {
+                                let acquired = row.acquired;
+                                let held = move || acquired.map(|a| fmt_time_short(now.read().duration_since(a)));
+                                let name = row.name.clone();
+
+                                view! {
+                                    <tr
+                                        class="divide-x-1 divide-neutral-600 hover:bg-neutral-800 cursor-pointer"
+                                        on:click=move |_| fly_to(name.clone())
+                                    >
+                                        <td class="border-b-1 border-neutral-600 p-1 whitespace-nowrap">{row.name.to_string()}</td>
+                                        <td class="border-b-1 border-neutral-600 p-1 whitespace-nowrap">
+                                            {row.guild.name.to_string()}" "
+                                            <span class="font-mono">"["{row.guild.prefix.to_string()}"]"</span>
+                                        </td>
+                                        <td class="border-b-1 border-neutral-600 p-1 text-right whitespace-nowrap">{held}</td>
+                                        <td class="border-b-1 border-neutral-600 p-1 whitespace-nowrap" style:color=row.treasury.color()>{row.treasury.to_string()}</td>
+                                        <td class="border-b-1 border-neutral-600 p-1 whitespace-nowrap" style:color=row.defences.color()>{row.defences.to_string()}</td>
+                                        <td class="border-b-1 border-neutral-600 p-1">{row.hq.then_some("HQ")}</td>
+                                        {row.resources.iter().map(|res| view! {
+                                            <td class="border-b-1 border-neutral-600 p-1 text-right">{res.generation}</td>
+                                            <td class="border-b-1 border-neutral-600 p-1 text-right whitespace-nowrap">{res.stored}"/"{res.limit}</td>
+                                        }).collect::<Vec<_>>()}
+                                    </tr>
+                                }
+                            }
+                        />
+                    </tbody>
+                </table>
+            </div>
+        </div>
+    }
+}
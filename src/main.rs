@@ -21,6 +21,7 @@ mod modes;
 mod notfound;
 mod sectimer;
 mod settings;
+mod urlstate;
 mod util;
 mod wynnmap;
 
@@ -44,6 +45,7 @@ pub fn App() -> impl IntoView {
                 <Routes fallback=NotFound>
                     <Route path=path!("") view=WarMap />
                     <Route path=path!("plan") view=PlanningMap />
+                    <Route path=path!("plan/:id") view=PlanningMap />
                     <Route path=path!("gather") view=GatherMap />
                 </Routes>
             </Router>
@@ -6,14 +6,20 @@ use wynnmap_types::gather::{GatherSpots, Material};
 use crate::{
     components::{checkbox::Checkbox, sidebar::Sidebar, sidecard::SideCard},
     datasource,
+    dialog::palette::CommandPalette,
     modes::gather::noderender::NodeRenderer,
     settings::use_toggle,
+    urlstate::{UrlState, UrlToggles, use_url_state},
+    util::titlecase,
     wynnmap::{WynnMap, context::RelMousePos, maptile::WithDefaultMapTiles},
 };
 
 mod clustering;
 mod noderender;
 
+/// Prefix of the setting names of the material toggles
+const MAT_TOGGLE_PREFIX: &str = "gather-mat-";
+
 #[component]
 pub fn GatherMap() -> impl IntoView {
     let nodes = RwSignal::new(GatherSpots::default());
@@ -36,11 +42,36 @@ pub fn GatherMap() -> impl IntoView {
 
     spawn_local(load_data(nodes));
 
+    // territories are only used for searching
+    let terrs = RwSignal::new(BTreeMap::new());
+    spawn_local(async move {
+        if let Ok(data) = datasource::get_terrs().await {
+            terrs.set(data);
+        }
+    });
+
+    // spot picked from the command palette
+    let selected = RwSignal::new(None::<[i32; 2]>);
+
     let RelMousePos(mouse_rel) = expect_context();
     let hovered = RwSignal::new(Vec::new());
 
     let toggles = RwSignal::new(Vec::new());
 
+    use_url_state(UrlState {
+        toggles: Some(UrlToggles {
+            prefix: MAT_TOGGLE_PREFIX,
+            toggles: Signal::derive(move || {
+                toggles
+                    .read()
+                    .iter()
+                    .map(|t: &MatToggle| (t.mat.name.clone(), t.toggle))
+                    .collect()
+            }),
+        }),
+        ..Default::default()
+    });
+
     Effect::new(move |_| {
         toggles.update(|toggles| {
             toggles.clear();
@@ -48,7 +79,7 @@ pub fn GatherMap() -> impl IntoView {
             for (n, mat) in nodes.read().resources.iter().enumerate() {
                 toggles.push(MatToggle {
                     mat: mat.clone(),
-                    toggle: use_toggle(format!("gather-mat-{}", mat.name), true),
+                    toggle: use_toggle(format!("{MAT_TOGGLE_PREFIX}{}", mat.name), true),
                     count: nodes
                         .read()
                         .spots
@@ -87,12 +118,20 @@ pub fn GatherMap() -> impl IntoView {
     let search_str = RwSignal::new(String::new());
 
     view! {
-        <WynnMap>
+        <WynnMap onclick={Callback::new(move |_| selected.set(None))}>
             <WithDefaultMapTiles grayscale=true />
 
             <NodeRenderer nodes data hovered hidden={hidelist} />
+
+            {move || selected.get().map(|[x, z]| view! {
+                <svg style="position: absolute; overflow: visible">
+                    <circle cx=x cy=z r=12 fill="none" stroke="white" stroke-width=3 vector-effect="non-scaling-stroke" />
+                </svg>
+            })}
         </WynnMap>
 
+        <CommandPalette terrs spots={nodes} selected_spot={selected} />
+
         <SideCard hover=true>
             <div>
                 <span>"X: "{move || mouse_rel.get().map(|p| p[0])}" / Y: "{move || mouse_rel.get().map(|p| p[1])}</span>
@@ -150,15 +189,3 @@ fn ToggleComponent(toggle: MatToggle) -> impl IntoView {
         </Checkbox>
     }
 }
-
-/// Format a string in Titlecase
-fn titlecase(name: impl AsRef<str>) -> String {
-    let mut c = name.as_ref().chars();
-    match c.next() {
-        None => String::new(),
-        Some(f) => f
-            .to_uppercase()
-            .chain(c.flat_map(char::to_lowercase))
-            .collect(),
-    }
-}
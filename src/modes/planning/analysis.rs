@@ -0,0 +1,147 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{
+    claim::ClaimAnalysis,
+    guild::Guild,
+    terr::{TerrState, Territory},
+};
+
+use crate::settings::use_toggle;
+
+/// Sidebar panel analysing the claim of a guild in the plan
+#[component]
+pub fn ClaimAnalysisPanel(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
+    /// The territory selected on the map, set when a territory in the panel is clicked
+    selected: RwSignal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let show = use_toggle("claimanalysis", false);
+
+    // guilds with territories in the plan sorted by their territory count
+    let guilds = Memo::new(move |_| {
+        let mut counts: HashMap<Guild, usize> = HashMap::new();
+
+        for s in state
+            .read()
+            .values()
+            .filter(|s| s.guild != Guild::default())
+        {
+            *counts.entry(s.guild.clone()).or_default() += 1;
+        }
+
+        let mut guilds: Vec<_> = counts.into_iter().collect();
+        guilds.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.prefix.cmp(&b.0.prefix)));
+
+        guilds.into_iter().map(|(g, _)| g).collect::<Vec<_>>()
+    });
+
+    let picked = RwSignal::new(None::<Arc<str>>);
+
+    // the picked guild falling back to the largest guild
+    let prefix = Memo::new(move |_| {
+        let guilds = guilds.read();
+
+        picked
+            .get()
+            .filter(|p| guilds.iter().any(|g| g.prefix == *p))
+            .or_else(|| guilds.first().map(|g| g.prefix.clone()))
+    });
+
+    let analysis = Memo::new(move |_| {
+        let prefix = prefix.get()?;
+
+        show.get()
+            .then(|| ClaimAnalysis::for_guild(&terrs.read(), &state.read(), &prefix))
+    });
+
+    let terr_link = move |name: Arc<str>| {
+        view! {
+            <button class="hover:underline text-left" on:click={
+                let name = name.clone();
+                move |_| selected.set(Some(name.clone()))
+            }>
+                {name.to_string()}
+            </button>
+        }
+    };
+
+    let content = move || {
+        let Some(a) = analysis.get() else {
+            return view! { <p class="text-neutral-400">"No guilds own territories in this plan."</p> }
+                .into_any();
+        };
+
+        let res = a.production;
+
+        view! {
+            <div class="flex flex-col gap-1">
+                <p>"Territories: "{a.territories}</p>
+                <p title="Number of disconnected parts of the claim">"Components: "{a.components}</p>
+
+                <h3 class="text-neutral-400">"Base production"</h3>
+                <div class="grid grid-cols-2 gap-x-2 text-base">
+                    <span>"Emeralds: "{res.emerald}</span>
+                    <span>"Ore: "{res.ore}</span>
+                    <span>"Crops: "{res.crop}</span>
+                    <span>"Fish: "{res.fish}</span>
+                    <span>"Wood: "{res.wood}</span>
+                </div>
+
+                <h3 class="text-neutral-400">"Best HQ"</h3>
+                {a.hq_candidate.map(|hq| view! {
+                    <div class="text-base">
+                        {terr_link(hq.name)}
+                        <p>{format!("{} externals, {} connections", hq.externals, hq.conns)}</p>
+                        <p>{format!("Tower multiplier: {:.2}x", hq.multiplier)}</p>
+                    </div>
+                })}
+
+                <h3 class="text-neutral-400">{format!("Border territories ({})", a.borders.len())}</h3>
+                <div class="flex flex-col text-base max-h-40 overflow-y-auto">
+                    {a.borders.into_iter().map(terr_link).collect::<Vec<_>>()}
+                </div>
+            </div>
+        }
+        .into_any()
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <button class="flex justify-between items-center" on:click={move |_| show.update(|s| *s = !*s)}>
+                <h2>"Claim analysis"</h2>
+                <Show when=move || !show.get()><icons::ChevronUp size=24/></Show>
+                <Show when=move || show.get()><icons::ChevronDown size=24/></Show>
+            </button>
+
+            <div class="flex flex-col gap-2" class:hidden={move || !show.get()}>
+                <select class="p-1 rounded border-1 border-neutral-600" on:input:target=move |ev| {
+                    picked.set(Some(Arc::from(ev.target().value())));
+                }>
+                    <For
+                        each=move || guilds.get()
+                        key=|g| g.clone()
+                        children=move |g| {
+                            let selected = {
+                                let own = g.prefix.clone();
+                                move || prefix.read().as_ref() == Some(&own)
+                            };
+
+                            view! {
+                                <option value={g.prefix.to_string()} selected={selected}>
+                                    {g.name} " ["{g.prefix.to_string()}"]"
+                                </option>
+                            }
+                        }
+                    />
+                </select>
+
+                {content}
+            </div>
+        </div>
+    }
+}
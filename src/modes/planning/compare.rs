@@ -0,0 +1,375 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::{prelude::*, task::spawn_local};
+use wynnmap_types::{
+    guild::Guild,
+    resources::BaseResGen,
+    terr::{TerrState, Territory},
+};
+
+use crate::{
+    datasource,
+    dialog::planning::compare_summary,
+    dialog::{Dialogs, info::info_dialog},
+    modes::planning::{
+        plan::{Plan, owner_state},
+        slots::PlanSlots,
+    },
+    wynnmap::highlight::HighlightView,
+};
+
+/// Highlight color of territories gained by the focused guild
+pub const GAINED_COLOR: &str = "#22c55e";
+/// Highlight color of territories lost by the focused guild
+pub const LOST_COLOR: &str = "#ef4444";
+/// Highlight color of territories kept by the focused guild
+pub const UNCHANGED_COLOR: &str = "#ffffff";
+/// Highlight color of territories changing owner when no guild is focused
+pub const CHANGED_COLOR: &str = "#f59e0b";
+
+/// What the plan is compared against
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareTarget {
+    /// The current ownership of the live map
+    Live,
+    /// Another saved plan
+    Plan(u64),
+}
+
+/// The changes of a single guild between the compared state and the plan
+#[derive(Clone, Debug, PartialEq)]
+pub struct GuildDiff {
+    pub guild: Guild,
+    /// Number of territories owned in the compared state
+    pub before: usize,
+    /// Number of territories owned in the plan
+    pub after: usize,
+    pub gained: usize,
+    pub lost: usize,
+    /// Base resource generation of the gained territories
+    pub res_gained: BaseResGen,
+    /// Base resource generation of the lost territories
+    pub res_lost: BaseResGen,
+}
+
+impl GuildDiff {
+    fn new(guild: &Guild) -> Self {
+        Self {
+            guild: guild.clone(),
+            before: 0,
+            after: 0,
+            gained: 0,
+            lost: 0,
+            res_gained: BaseResGen::default(),
+            res_lost: BaseResGen::default(),
+        }
+    }
+}
+
+/// The territories highlighted by the compare overlay
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompareHighlights {
+    pub gained: BTreeSet<Arc<str>>,
+    pub lost: BTreeSet<Arc<str>>,
+    pub unchanged: BTreeSet<Arc<str>>,
+    /// Territories changing owner, only filled when no guild is focused
+    pub changed: BTreeSet<Arc<str>>,
+}
+
+/// State of the plan compare mode
+#[derive(Clone, Copy)]
+pub struct Compare {
+    /// What the plan is compared against, if compare mode is on
+    pub target: RwSignal<Option<CompareTarget>>,
+    /// Tag of the guild whose changes are highlighted
+    pub focus: RwSignal<Option<Arc<str>>>,
+    /// The ownership the plan is compared against
+    pub before: RwSignal<BTreeMap<Arc<str>, TerrState>>,
+    dialogs: Dialogs,
+}
+
+impl Compare {
+    pub fn new() -> Self {
+        Self {
+            target: RwSignal::new(None),
+            focus: RwSignal::new(None),
+            before: RwSignal::new(BTreeMap::new()),
+            dialogs: use_context::<Dialogs>().expect("Dialogs context not found"),
+        }
+    }
+
+    /// Start comparing against the target loading its ownership
+    ///
+    /// Saved plans made on other territories are compared on the territories of the current plan.
+    pub fn set_target(
+        self,
+        target: Option<CompareTarget>,
+        slots: PlanSlots,
+        terrs: &BTreeMap<Arc<str>, Territory>,
+    ) {
+        self.target.set(target);
+        self.before.set(BTreeMap::new());
+
+        match target {
+            None => {}
+            Some(CompareTarget::Live) => {
+                let dialogs = self.dialogs;
+
+                spawn_local(async move {
+                    match datasource::get_state().await {
+                        // the target may have changed while loading
+                        Ok(state) if self.target.get_untracked() == target => {
+                            self.before.set(state.terrs);
+                        }
+                        Ok(_) => {}
+                        Err(err) => {
+                            self.target.set(None);
+                            dialogs.add("err_compare", move || {
+                                info_dialog(
+                                    String::from("Failed to load the map state"),
+                                    view! {
+                                        <pre class="p-2 bg-neutral-800 rounded my-1">{format!("{err}")}</pre>
+                                    },
+                                )
+                            });
+                        }
+                    }
+                });
+            }
+            Some(CompareTarget::Plan(id)) => {
+                let Some(data) = slots.read(id) else {
+                    self.target.set(None);
+                    return;
+                };
+
+                self.before
+                    .set(owner_state(terrs, &data.owned_territories, &data.hqs));
+            }
+        }
+    }
+
+    /// Sort the territories of the plan into the highlighted groups
+    pub fn highlights(self, after: &BTreeMap<Arc<str>, TerrState>) -> CompareHighlights {
+        let mut highlights = CompareHighlights::default();
+
+        if self.target.read().is_none() {
+            return highlights;
+        }
+
+        let before = self.before.read();
+        let focus = self.focus.read();
+
+        for (name, state) in after {
+            let old = before.get(name).map(|s| &s.guild.prefix);
+            let new = &state.guild.prefix;
+
+            match focus.as_ref() {
+                Some(focus) => {
+                    let owned_before = old == Some(focus);
+                    let owned_after = new == focus;
+
+                    let set = match (owned_before, owned_after) {
+                        (false, true) => &mut highlights.gained,
+                        (true, false) => &mut highlights.lost,
+                        (true, true) => &mut highlights.unchanged,
+                        (false, false) => continue,
+                    };
+                    set.insert(name.clone());
+                }
+                None => {
+                    if old != Some(new) {
+                        highlights.changed.insert(name.clone());
+                    }
+                }
+            }
+        }
+
+        highlights
+    }
+
+    /// Summarise the changes of every guild sorted by the number of territories in the plan
+    pub fn summarize(
+        self,
+        terrs: &BTreeMap<Arc<str>, Territory>,
+        after: &BTreeMap<Arc<str>, TerrState>,
+    ) -> Vec<GuildDiff> {
+        let before = self.before.read();
+        let mut diffs: BTreeMap<Arc<str>, GuildDiff> = BTreeMap::new();
+
+        for (name, terr) in terrs {
+            let old = before
+                .get(name)
+                .map(|s| s.guild.clone())
+                .unwrap_or_default();
+            let new = after.get(name).map(|s| s.guild.clone()).unwrap_or_default();
+            let changed = old.prefix != new.prefix;
+
+            if old != Guild::default() {
+                let diff = diffs
+                    .entry(old.prefix.clone())
+                    .or_insert_with(|| GuildDiff::new(&old));
+                diff.before += 1;
+
+                if changed {
+                    diff.lost += 1;
+                    diff.res_lost += terr.generates;
+                }
+            }
+
+            if new != Guild::default() {
+                let diff = diffs
+                    .entry(new.prefix.clone())
+                    .or_insert_with(|| GuildDiff::new(&new));
+                diff.after += 1;
+
+                if changed {
+                    diff.gained += 1;
+                    diff.res_gained += terr.generates;
+                }
+            }
+        }
+
+        let mut diffs: Vec<_> = diffs.into_values().collect();
+        diffs.sort_by(|a, b| b.after.cmp(&a.after).then_with(|| b.before.cmp(&a.before)));
+
+        diffs
+    }
+}
+
+/// Map layers highlighting the changes between the compared state and the plan
+#[component]
+pub fn CompareView(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into)] highlights: Signal<CompareHighlights>,
+) -> impl IntoView {
+    view! {
+        <HighlightView terrs={terrs} highlighted={Signal::derive(move || highlights.read().unchanged.clone())} color=UNCHANGED_COLOR />
+        <HighlightView terrs={terrs} highlighted={Signal::derive(move || highlights.read().gained.clone())} color=GAINED_COLOR />
+        <HighlightView terrs={terrs} highlighted={Signal::derive(move || highlights.read().lost.clone())} color=LOST_COLOR />
+        <HighlightView terrs={terrs} highlighted={Signal::derive(move || highlights.read().changed.clone())} color=CHANGED_COLOR />
+    }
+}
+
+/// Sidebar controls for picking what the plan is compared against and the highlighted guild
+#[component]
+pub fn CompareControls(
+    compare: Compare,
+    plan: Plan,
+    slots: PlanSlots,
+    #[prop(into)] diffs: Signal<Vec<GuildDiff>>,
+) -> impl IntoView {
+    let Compare { target, focus, .. } = compare;
+    let dialogs = compare.dialogs;
+
+    let ontarget = move |value: String| {
+        let target = match value.as_str() {
+            "live" => Some(CompareTarget::Live),
+            v => v.parse().ok().map(CompareTarget::Plan),
+        };
+
+        compare.set_target(target, slots, &plan.terrs.read_untracked());
+    };
+
+    // saved plans other than the one being edited
+    let other_plans = move || {
+        let current = slots.current.get();
+
+        slots
+            .slots
+            .get()
+            .into_iter()
+            .filter(|s| Some(s.id) != current)
+            .collect::<Vec<_>>()
+    };
+
+    let legend = move || {
+        let items = if focus.read().is_some() {
+            vec![
+                (GAINED_COLOR, "Gained"),
+                (LOST_COLOR, "Lost"),
+                (UNCHANGED_COLOR, "Unchanged"),
+            ]
+        } else {
+            vec![(CHANGED_COLOR, "Changed owner")]
+        };
+
+        items
+            .into_iter()
+            .map(|(color, label)| {
+                view! {
+                    <span class="flex items-center gap-1">
+                        <span class="inline-block size-3 rounded-sm" style:background-color=color></span>
+                        {label}
+                    </span>
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <label class="flex flex-col">
+                "Compare with:"
+                <select class="p-1 rounded border-1 border-neutral-600" on:input:target=move |ev| ontarget(ev.target().value())>
+                    <option value="" selected={move || target.get().is_none()}>"Off"</option>
+                    <option value="live" selected={move || target.get() == Some(CompareTarget::Live)}>"Live map"</option>
+                    <For
+                        each=other_plans
+                        key=|slot| slot.clone()
+                        children=move |slot| {
+                            let id = slot.id;
+
+                            view! {
+                                <option value={id} selected={move || target.get() == Some(CompareTarget::Plan(id))}>
+                                    {slot.name}
+                                </option>
+                            }
+                        }
+                    />
+                </select>
+            </label>
+
+            <div class="flex flex-col gap-2" class:hidden={move || target.read().is_none()}>
+                <select class="p-1 rounded border-1 border-neutral-600" on:input:target=move |ev| {
+                    let value = ev.target().value();
+                    focus.set((!value.is_empty()).then(|| Arc::from(value)));
+                }>
+                    <option value="" selected={move || focus.read().is_none()}>"All guilds"</option>
+                    <For
+                        each=move || diffs.get()
+                        key=|diff| diff.guild.prefix.clone()
+                        children=move |diff| {
+                            let prefix = diff.guild.prefix.clone();
+                            let selected = {
+                                let prefix = prefix.clone();
+                                move || focus.read().as_ref() == Some(&prefix)
+                            };
+
+                            view! {
+                                <option value={prefix.to_string()} selected={selected}>
+                                    {diff.guild.name} " ["{prefix.to_string()}"]"
+                                </option>
+                            }
+                        }
+                    />
+                </select>
+
+                <div class="flex flex-wrap gap-x-3 text-sm">{legend}</div>
+
+                <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={
+                    let owner = Owner::new();
+                    move |_| {
+                        owner.with(move || {
+                            dialogs.add("compare_summary", move || compare_summary(diffs));
+                        });
+                    }
+                }>
+                    "Summary"
+                </button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{guild::Guild, terr::Territory, tower::TowerUpgrades};
+
+use crate::modes::planning::plan::Plan;
+
+/// Maximum number of edits kept in the history
+const MAX_HISTORY: usize = 200;
+
+/// Undo and redo history of a plan
+///
+/// The history stores the changes made by the edit functions of [`Plan`] instead of copies of the plan.
+#[derive(Clone, Copy)]
+pub struct History {
+    undo: RwSignal<Vec<Edit>>,
+    redo: RwSignal<Vec<Edit>>,
+    /// Whether the next edit may be merged into the last one
+    open: RwSignal<bool>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            undo: RwSignal::new(Vec::new()),
+            redo: RwSignal::new(Vec::new()),
+            open: RwSignal::new(false),
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.read().is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.read().is_empty()
+    }
+
+    /// Forget all edits, for example after switching to another plan
+    pub fn clear(&self) {
+        self.undo.set(Vec::new());
+        self.redo.set(Vec::new());
+        self.seal();
+    }
+
+    /// Stop merging edits into the last one, for example at the end of a drag
+    pub fn seal(&self) {
+        self.open.set(false);
+    }
+
+    /// Record changes which have been applied to the plan as a single edit
+    ///
+    /// Consecutive edits with the same key are merged into a single history entry until the history is sealed.
+    pub(super) fn push(&self, key: Option<&'static str>, changes: Vec<Change>) {
+        if changes.is_empty() {
+            return;
+        }
+
+        let merge = key.is_some()
+            && self.open.get_untracked()
+            && self
+                .undo
+                .read_untracked()
+                .last()
+                .is_some_and(|e| e.key == key);
+
+        self.undo.update(|u| {
+            match u.last_mut() {
+                Some(last) if merge => last.merge(changes),
+                _ => u.push(Edit { key, changes }),
+            }
+
+            if u.len() > MAX_HISTORY {
+                u.remove(0);
+            }
+        });
+        self.redo.update(Vec::clear);
+        self.open.set(key.is_some());
+    }
+
+    /// Revert the last edit
+    pub fn undo(&self, plan: Plan) {
+        let Some(edit) = self.undo.try_update(Vec::pop).flatten() else {
+            return;
+        };
+
+        edit.apply(plan, false);
+        self.redo.update(|r| r.push(edit));
+        self.seal();
+    }
+
+    /// Apply the last reverted edit again
+    pub fn redo(&self, plan: Plan) {
+        let Some(edit) = self.redo.try_update(Vec::pop).flatten() else {
+            return;
+        };
+
+        edit.apply(plan, true);
+        self.undo.update(|u| u.push(edit));
+        self.seal();
+    }
+}
+
+/// The owner of a territory, if it has one
+type Owner = Option<ArcRwSignal<Guild>>;
+
+/// A single change of a plan from the old to the new value
+pub(super) enum Change {
+    /// Owner of a territory
+    Owner(Arc<str>, Owner, Owner),
+    /// Whether a territory is a guild hq
+    Hq(Arc<str>, bool, bool),
+    /// Note written on a territory
+    Note(Arc<str>, Option<String>, Option<String>),
+    /// Tower upgrades of a territory
+    Upgrades(Arc<str>, Option<TowerUpgrades>, Option<TowerUpgrades>),
+    /// The list of guilds
+    Guilds(Vec<ArcRwSignal<Guild>>, Vec<ArcRwSignal<Guild>>),
+    /// Value of a guild edited in place
+    Guild(ArcRwSignal<Guild>, Guild, Guild),
+    /// The whole plan replaced at once, for example by loading a file
+    Replace(Box<Snapshot>, Box<Snapshot>),
+}
+
+impl Change {
+    /// Apply the change to the plan either forwards or backwards
+    pub(super) fn apply(&self, plan: Plan, forward: bool) {
+        fn pick<T>(old: T, new: T, forward: bool) -> T {
+            if forward { new } else { old }
+        }
+
+        match self {
+            Self::Owner(name, old, new) => plan.owned.update(|o| match pick(old, new, forward) {
+                Some(guild) => _ = o.insert(name.clone(), guild.clone()),
+                None => _ = o.remove(name),
+            }),
+            Self::Hq(name, old, new) => plan.hqs.update(|h| {
+                if *pick(old, new, forward) {
+                    h.insert(name.clone());
+                } else {
+                    h.remove(name);
+                }
+            }),
+            Self::Note(name, old, new) => plan.notes.update(|n| match pick(old, new, forward) {
+                Some(note) => _ = n.insert(name.clone(), note.clone()),
+                None => _ = n.remove(name),
+            }),
+            Self::Upgrades(name, old, new) => {
+                plan.upgrades.update(|u| match pick(old, new, forward) {
+                    Some(up) => _ = u.insert(name.clone(), *up),
+                    None => _ = u.remove(name),
+                });
+            }
+            Self::Guilds(old, new) => plan.guilds.set(pick(old, new, forward).clone()),
+            Self::Guild(guild, old, new) => guild.set(pick(old, new, forward).clone()),
+            Self::Replace(old, new) => pick(old, new, forward).restore(plan),
+        }
+    }
+
+    /// Combine this change with a following change of the same value
+    ///
+    /// Returns the following change if it changes another value.
+    fn merge(&mut self, next: Self) -> Option<Self> {
+        match (self, next) {
+            (Self::Owner(a, _, new), Self::Owner(b, _, next)) if *a == b => *new = next,
+            (Self::Hq(a, _, new), Self::Hq(b, _, next)) if *a == b => *new = next,
+            (Self::Note(a, _, new), Self::Note(b, _, next)) if *a == b => *new = next,
+            (Self::Upgrades(a, _, new), Self::Upgrades(b, _, next)) if *a == b => *new = next,
+            (Self::Guilds(_, new), Self::Guilds(_, next)) => *new = next,
+            (Self::Guild(a, _, new), Self::Guild(b, _, next)) if *a == b => *new = next,
+            (_, next) => return Some(next),
+        }
+
+        None
+    }
+}
+
+/// Copy of the whole plan for edits which replace it at once
+#[derive(PartialEq)]
+pub(super) struct Snapshot {
+    terrs: BTreeMap<Arc<str>, Territory>,
+    guilds: Vec<ArcRwSignal<Guild>>,
+    /// Values of the guilds as they may be edited in place
+    values: Vec<Guild>,
+    owned: BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
+    hqs: BTreeSet<Arc<str>>,
+    notes: BTreeMap<Arc<str>, String>,
+    upgrades: BTreeMap<Arc<str>, TowerUpgrades>,
+}
+
+impl Snapshot {
+    pub(super) fn take(plan: Plan) -> Self {
+        let guilds = plan.guilds.get_untracked();
+
+        Self {
+            terrs: plan.terrs.get_untracked(),
+            values: guilds.iter().map(GetUntracked::get_untracked).collect(),
+            guilds,
+            owned: plan.owned.get_untracked(),
+            hqs: plan.hqs.get_untracked(),
+            notes: plan.notes.get_untracked(),
+            upgrades: plan.upgrades.get_untracked(),
+        }
+    }
+
+    fn restore(&self, plan: Plan) {
+        for (guild, value) in self.guilds.iter().zip(&self.values) {
+            if *guild.read_untracked() != *value {
+                guild.set(value.clone());
+            }
+        }
+
+        plan.terrs.set(self.terrs.clone());
+        plan.guilds.set(self.guilds.clone());
+        plan.owned.set(self.owned.clone());
+        plan.hqs.set(self.hqs.clone());
+        plan.notes.set(self.notes.clone());
+        plan.upgrades.set(self.upgrades.clone());
+    }
+}
+
+/// The changes made by a single edit
+struct Edit {
+    key: Option<&'static str>,
+    changes: Vec<Change>,
+}
+
+impl Edit {
+    /// Add the changes of the following edit
+    fn merge(&mut self, changes: Vec<Change>) {
+        'next: for mut change in changes {
+            for existing in self.changes.iter_mut().rev() {
+                match existing.merge(change) {
+                    Some(c) => change = c,
+                    None => continue 'next,
+                }
+            }
+
+            self.changes.push(change);
+        }
+    }
+
+    /// Apply the edit to the plan either forwards or backwards
+    fn apply(&self, plan: Plan, forward: bool) {
+        if forward {
+            for change in &self.changes {
+                change.apply(plan, true);
+            }
+        } else {
+            for change in self.changes.iter().rev() {
+                change.apply(plan, false);
+            }
+        }
+    }
+}
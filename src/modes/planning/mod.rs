@@ -1,30 +1,60 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
-use leptos::{leptos_dom::logging::console_log, prelude::*, task::spawn_local};
-use leptos_router::hooks::use_location;
-use wynnmap_types::{guild::Guild, terr::TerrState};
+use leptos::{ev, leptos_dom::logging::console_log, prelude::*, task::spawn_local};
+use leptos_router::hooks::{use_location, use_params_map, use_query_map};
+use web_sys::{
+    HtmlElement, KeyboardEvent,
+    wasm_bindgen::{JsCast, JsValue},
+};
+use wynnmap_types::{
+    routing::TradeRoutes,
+    tower::{MAX_BONUS, MAX_UPGRADE, TowerUpgrades},
+};
 
 use crate::{
     components::{
         gleaderboard::Gleaderboard,
+        incrementor::Incrementor,
         sidebar::Sidebar,
         sidecard::{
             SideCard,
+            routes::RouteInfo,
             terr::{GuildName, TerrInfo},
         },
     },
     datasource,
-    dialog::{self, Dialogs, info::info_dialog},
+    dialog::{self, Dialogs, info::info_dialog, palette::CommandPalette},
+    modes::planning::{
+        analysis::ClaimAnalysisPanel,
+        compare::{Compare, CompareControls, CompareView},
+        plan::Plan,
+        session::{SESSION_PARAM, Session, SessionConnection, SessionPanel, SessionPresenceView},
+        slots::PlanSlots,
+        tools::{ToolBar, Tools},
+    },
     settings::use_toggle,
-    wynnmap::{WynnMap, conns::Connections, maptile::WithDefaultMapTiles, terrs::TerrView},
+    urlstate::{UrlState, use_url_state},
+    wynnmap::{
+        WynnMap, conns::Connections, maptile::WithDefaultMapTiles, routes::TradeRouteView,
+        selection::SelectionView, terrs::TerrView,
+    },
 };
 
+pub mod analysis;
+pub mod compare;
+pub mod history;
+pub mod plan;
+pub mod session;
+pub mod slots;
+pub mod tools;
+
 #[component]
 pub fn PlanningMap() -> impl IntoView {
     let dialogs = use_context::<Dialogs>().expect("Dialogs context not found");
     let show_guild_leaderboard = use_toggle("gleaderboard", true);
 
     let location = use_location();
+    let params = use_params_map();
     // read a share string from the url
     let sharedata = move || {
         // get the hash part of the url
@@ -66,12 +96,62 @@ pub fn PlanningMap() -> impl IntoView {
     // console_log(&format!("{:?}", sharedata));
 
     let show_conns = use_toggle("conns", true);
+    let show_routes = use_toggle("traderoutes", false);
+
+    let plan = Plan::new();
+    let Plan {
+        terrs,
+        owned,
+        history,
+        ..
+    } = plan;
+
+    // undo and redo shortcuts
+    let handle = window_event_listener(ev::keydown, move |e: KeyboardEvent| {
+        // leave text fields to their own undo history
+        let in_text_field = e
+            .target()
+            .and_then(|t| t.dyn_into::<HtmlElement>().ok())
+            .is_some_and(|t| matches!(t.tag_name().as_str(), "INPUT" | "TEXTAREA"));
+
+        if !(e.ctrl_key() || e.meta_key()) || in_text_field {
+            return;
+        }
+
+        match e.key().to_lowercase().as_str() {
+            "z" if e.shift_key() => history.redo(plan),
+            "z" => history.undo(plan),
+            "y" => history.redo(plan),
+            _ => return,
+        }
+
+        e.prevent_default();
+    });
+    on_cleanup(move || handle.remove());
+
+    // saved plans with the last plan restored on open
+    let slots = PlanSlots::load();
+    slots.restore(plan);
 
-    let terrs = RwSignal::new(BTreeMap::new());
+    // the current territories which new plans are created on
+    let live_terrs = plan.live_terrs;
 
-    let load_terrs = move |terrs: RwSignal<_>| async move {
+    // collaborative planning session joined from an invite link
+    let session = Session::new();
+    if let Some(room) = use_query_map().read_untracked().get(SESSION_PARAM) {
+        session.join(plan, slots, Arc::from(room), BTreeMap::new());
+    }
+
+    let load_terrs = move || async move {
         match datasource::get_terrs().await {
-            Ok(data) => terrs.set(data),
+            Ok(data) => {
+                // restored plans keep the territories they were made on
+                if terrs.read_untracked().is_empty() {
+                    terrs.set(data.clone());
+                }
+
+                live_terrs.set(data);
+            }
             Err(err) => {
                 if !dialogs.contains("err_maptiles") {
                     dialogs.add("err_maptiles", move || {
@@ -88,70 +168,137 @@ pub fn PlanningMap() -> impl IntoView {
         }
     };
 
-    spawn_local(load_terrs(terrs));
+    spawn_local(load_terrs());
 
-    let guilds: RwSignal<Vec<ArcRwSignal<Guild>>> =
-        RwSignal::new(vec![ArcRwSignal::new(Guild::default())]);
-    let owned: RwSignal<BTreeMap<Arc<str>, ArcRwSignal<Guild>>> = RwSignal::new(BTreeMap::new());
-
-    // apply the share string when territories have loaded
+    // autosave the plan shortly after it has been changed
+    let autosave = StoredValue::new(None::<TimeoutHandle>);
     Effect::new(move || {
-        if !terrs.read().is_empty()
-            && let Some(data) = sharedata()
-        {
-            if data.verify_terrhash(&terrs.read()) {
-                let (gu, ow) = data.into_data(&terrs.read());
-
-                guilds.set(gu);
-                owned.set(ow);
-
-                // ensure that the sharedata is only decoded once
-                let _ = window().location().set_hash("");
-            } else {
-                dialogs.add("info", move || {
-                    dialog::info::info_dialog(
-                        String::from("Territory hash mismatch"),
-                        view! {
-                            <p>"Territories have changed since the creation of this share URL. This URL can no longer be decoded."</p>
-                        },
-                    )
-                });
-            }
+        plan.track();
+
+        if let Some(handle) = autosave.get_value() {
+            handle.clear();
+        }
+
+        autosave.set_value(
+            set_timeout_with_handle(
+                move || {
+                    autosave.set_value(None);
+                    slots.save(plan);
+                },
+                Duration::from_millis(500),
+            )
+            .ok(),
+        );
+    });
+    // save a pending change right away when leaving the page as the plan is gone once the timeout runs
+    on_cleanup(move || {
+        if let Some(handle) = autosave.try_update_value(Option::take).flatten() {
+            handle.clear();
+            slots.save(plan);
         }
     });
 
-    let mapowneds = Memo::new(move |_| {
-        let mut owners = BTreeMap::new();
-
-        for terr in (*terrs.read()).keys() {
-            if let Some(own) = owned.read().get(terr) {
-                let own = own.get();
-                owners.insert(
-                    terr.clone(),
-                    TerrState {
-                        guild: own,
-                        acquired: None,
-                        ..Default::default()
-                    },
-                );
-            } else {
-                owners.insert(
-                    terr.clone(),
-                    TerrState {
-                        guild: Guild::default(),
-                        acquired: None,
-                        ..Default::default()
-                    },
-                );
+    // shared plans are opened as a new plan so that they don't overwrite the current one
+    let load_shared = Callback::new(move |data| {
+        slots.create(
+            plan,
+            String::from("Shared plan"),
+            live_terrs.get_untracked(),
+        );
+        plan.load(data);
+    });
+
+    // plans opened from a short link are fetched from the server
+    let stored = RwSignal::new(None);
+    if let Some(id) = params.read_untracked().get("id") {
+        spawn_local(async move {
+            let data = datasource::get_plan(&id)
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|p| {
+                    dialog::planning::formats::urlshare::ShareUrlData::decode_string(p)
+                        .map_err(|e| e.to_string())
+                });
+
+            match data {
+                Ok(data) => stored.set(Some(data)),
+                Err(err) => {
+                    dialogs.add("info", move || {
+                        dialog::info::info_dialog(
+                            String::from("Failed to load shared plan"),
+                            view! {
+                                <pre>{err.clone()}</pre>
+                            },
+                        )
+                    });
+                }
             }
+        });
+    }
+
+    // apply the share string when territories have loaded
+    Effect::new(move || {
+        if live_terrs.read().is_empty() {
+            return;
         }
 
-        owners
+        let data = if let Some(data) = sharedata() {
+            // ensure that the sharedata is only decoded once
+            let _ = window().location().set_hash("");
+            data
+        } else if stored.with(Option::is_some)
+            && let Some(data) = stored.try_update(Option::take).flatten()
+        {
+            // reloading the page shouldn't open the plan again
+            let url = format!("/plan{}", window().location().search().unwrap_or_default());
+            let _ = window()
+                .history()
+                .and_then(|h| h.replace_state_with_url(&JsValue::NULL, "", Some(&url)));
+            data
+        } else {
+            return;
+        };
+
+        let mismatch = data.mismatch(&live_terrs.read());
+        let data = data.into_data(&live_terrs.read());
+
+        if mismatch.is_empty() {
+            load_shared.run(data);
+        } else {
+            dialogs.add("share_mismatch", move || {
+                dialog::planning::share_mismatch_dialog(data.clone(), mismatch.clone(), load_shared)
+            });
+        }
     });
 
+    let mapowneds = Memo::new(move |_| plan.state());
+
+    // changes of the plan compared against the live map or another plan
+    let compare = Compare::new();
+    let compare_highlights = Memo::new(move |_| compare.highlights(&mapowneds.read()));
+    let compare_diffs = Memo::new(move |_| compare.summarize(&terrs.read(), &mapowneds.read()));
+
     let hovered = RwSignal::new(None);
     let selected = RwSignal::new(None);
 
+    use_url_state(UrlState {
+        selected: Some(selected),
+        layers: vec![("conns", show_conns), ("traderoutes", show_routes)],
+        ..Default::default()
+    });
+
+    // trade routes of the guild owning the selected territory
+    let routes = Memo::new(move |_| {
+        let sel = selected.get()?;
+        let state = mapowneds.read();
+        let prefix = state.get(&sel)?.guild.prefix.clone();
+
+        TradeRoutes::for_guild(&terrs.read(), &state, &prefix)
+    });
+
+    let tools = Tools::new();
+    let ondrag = Callback::new(move |drag| tools.drag(plan, drag));
+
     // update the selected territory on click
     let onclick = Callback::new(move |pos| {
         selected.set(
@@ -164,7 +311,7 @@ pub fn PlanningMap() -> impl IntoView {
     });
 
     view! {
-        <WynnMap onclick=onclick>
+        <WynnMap onclick=onclick ondrag=ondrag>
             <WithDefaultMapTiles />
 
             // conns
@@ -174,8 +321,30 @@ pub fn PlanningMap() -> impl IntoView {
 
             // territories
             <TerrView terrs={terrs} state={mapowneds} hovered=hovered hide_timers=true />
+
+            // trade routes
+            <Show when={move || show_routes.get() && selected.read().is_some()}>
+                <TradeRouteView terrs={terrs} routes={routes} selected={selected} />
+            </Show>
+
+            // plan comparison
+            <Show when={move || compare.target.read().is_some()}>
+                <CompareView terrs={terrs} highlights={compare_highlights} />
+            </Show>
+
+            // selections of the other session participants
+            <SessionPresenceView session={session} terrs={terrs} />
+
+            // tool selection
+            <SelectionView terrs={terrs} selected={tools.selection} shape={tools.shape} />
         </WynnMap>
 
+        {move || session.room.get().map(|room| view! {
+            <SessionConnection session={session} room={room} plan={plan} selected={selected} selection={tools.selection} />
+        })}
+
+        <CommandPalette terrs state={mapowneds} selected />
+
         // hover box
         {move || if let Some(hovered) = hovered.get() {
             if selected.get().is_some() {
@@ -202,11 +371,46 @@ pub fn PlanningMap() -> impl IntoView {
 
         <Sidebar>
             <div class="flex-1 flex flex-col gap-2 p-2 text-lg">
+                <div class="flex gap-2 mx-2">
+                    <button
+                        class="p-2 border-neutral-600 border rounded-md hover:bg-neutral-700 disabled:text-neutral-600 disabled:hover:bg-transparent"
+                        title="Undo (Ctrl+Z)"
+                        disabled={move || !history.can_undo()}
+                        on:click={move |_| history.undo(plan)}
+                    >
+                        <icons::Undo2 size=24/>
+                    </button>
+                    <button
+                        class="p-2 border-neutral-600 border rounded-md hover:bg-neutral-700 disabled:text-neutral-600 disabled:hover:bg-transparent"
+                        title="Redo (Ctrl+Y)"
+                        disabled={move || !history.can_redo()}
+                        on:click={move |_| history.redo(plan)}
+                    >
+                        <icons::Redo2 size=24/>
+                    </button>
+                </div>
+
+                <ToolBar tools={tools} plan={plan} />
+
+                <CompareControls compare={compare} plan={plan} slots={slots} diffs={compare_diffs} />
+
                 <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
                     let owner = Owner::new();
                     move |_| {
                         owner.with(move || {
-                            dialogs.add("manage_guilds", move || dialog::planning::manage_guilds(guilds));
+                            dialogs.add("plan_browser", move || dialog::planning::plan_browser(plan, slots, live_terrs.into()));
+                        });
+                    }
+                }>
+                    "Plans"
+                    <span class="block text-sm text-neutral-400 truncate">{move || slots.current_name()}</span>
+                </button>
+
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
+                    let owner = Owner::new();
+                    move |_| {
+                        owner.with(move || {
+                            dialogs.add("manage_guilds", move || dialog::planning::manage_guilds(plan));
                         });
                     }
                 }>
@@ -217,12 +421,27 @@ pub fn PlanningMap() -> impl IntoView {
                     let owner = Owner::new();
                     move |_| {
                         owner.with(move || {
-                            dialogs.add("save", move || dialog::planning::save_dialog(terrs.into(), guilds, owned));
+                            dialogs.add("save", move || dialog::planning::save_dialog(plan));
                         });
                     }
                 }>
                     "Import/Export"
                 </button>
+
+                <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
+                    let owner = Owner::new();
+                    move |_| {
+                        owner.with(move || {
+                            dialogs.add("claim_builder", move || dialog::planning::claim_builder(plan, slots, live_terrs.into()));
+                        });
+                    }
+                }>
+                    "Claim builder"
+                </button>
+
+                <ClaimAnalysisPanel terrs={terrs} state={mapowneds} selected={selected} />
+
+                <SessionPanel session={session} slots={slots} />
             </div>
 
             // guild leaderboard
@@ -240,11 +459,11 @@ pub fn PlanningMap() -> impl IntoView {
                         terrs={terrs}
                         state={mapowneds}
                     />
-                    <GuildSelect
-                        terr_name={sel}
-                        terr_owners={owned}
-                        guilds={guilds}
-                    />
+                    <GuildSelect terr_name={sel} plan={plan} />
+                    <HqToggle terr_name={sel} plan={plan} />
+                    <TowerEditor terr_name={sel} plan={plan} />
+                    <NoteEditor terr_name={sel} plan={plan} />
+                    <RouteInfo name={sel} routes={routes} show_routes={show_routes} />
                 </SideCard>
             })
         })}
@@ -252,11 +471,13 @@ pub fn PlanningMap() -> impl IntoView {
 }
 
 #[component]
-pub fn GuildSelect(
-    terr_name: Signal<Arc<str>>,
-    terr_owners: RwSignal<BTreeMap<Arc<str>, ArcRwSignal<Guild>>>,
-    #[prop(into)] guilds: RwSignal<Vec<ArcRwSignal<Guild>>>,
-) -> impl IntoView {
+pub fn GuildSelect(terr_name: Signal<Arc<str>>, plan: Plan) -> impl IntoView {
+    let Plan {
+        guilds,
+        owned: terr_owners,
+        ..
+    } = plan;
+
     // find the index of the current owner if any otherwise default to none
     let owner = move || {
         // get the owner value of the territory
@@ -278,9 +499,7 @@ pub fn GuildSelect(
         if let Ok(idx) = sel.parse::<usize>()
             && let Some(guild) = guilds.read().get(idx)
         {
-            terr_owners.update(|o| {
-                o.insert(terr_name.get(), guild.clone());
-            });
+            plan.assign(None, [terr_name.get()], guild);
         }
     };
 
@@ -302,3 +521,174 @@ pub fn GuildSelect(
         </div>
     }
 }
+
+/// Button for setting or removing the hq status of a territory
+#[component]
+pub fn HqToggle(terr_name: Signal<Arc<str>>, plan: Plan) -> impl IntoView {
+    let Plan {
+        guilds,
+        owned: terr_owners,
+        hqs,
+        ..
+    } = plan;
+
+    let is_hq = move || hqs.read().contains(&*terr_name.read());
+
+    // only territories owned by an actual guild may be hqs
+    let owner = move || {
+        terr_owners
+            .read()
+            .get(&*terr_name.read())
+            .filter(|o| guilds.read().first() != Some(*o))
+            .cloned()
+    };
+
+    let toggle = move |_| {
+        let name = terr_name.get();
+
+        if is_hq() {
+            plan.set_hq(name, false);
+        } else if owner().is_some() {
+            plan.set_hq(name, true);
+        }
+    };
+
+    view! {
+        <div class="p-2" class:hidden={move || owner().is_none()}>
+            <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click=toggle>
+                {move || if is_hq() { "Remove HQ" } else { "Set as HQ" }}
+            </button>
+        </div>
+    }
+}
+
+/// Editor for the tower upgrades of a territory
+#[component]
+pub fn TowerEditor(terr_name: Signal<Arc<str>>, plan: Plan) -> impl IntoView {
+    let upgrades = plan.upgrades;
+
+    let current = move || {
+        upgrades
+            .read()
+            .get(&*terr_name.read())
+            .copied()
+            .unwrap_or_default()
+    };
+
+    let damage = RwSignal::new(0);
+    let attack = RwSignal::new(0);
+    let health = RwSignal::new(0);
+    let defence = RwSignal::new(0);
+    let aura = RwSignal::new(0);
+    let volley = RwSignal::new(0);
+
+    // load the upgrades of the territory into the editor
+    Effect::new(move || {
+        let up = current();
+
+        damage.set(up.damage);
+        attack.set(up.attack);
+        health.set(up.health);
+        defence.set(up.defence);
+        aura.set(up.aura);
+        volley.set(up.volley);
+    });
+
+    let edited = move || TowerUpgrades {
+        damage: damage.get(),
+        attack: attack.get(),
+        health: health.get(),
+        defence: defence.get(),
+        aura: aura.get(),
+        volley: volley.get(),
+    };
+
+    // write changes back into the plan
+    Effect::new(move || {
+        let up = edited();
+        let name = terr_name.get_untracked();
+
+        if upgrades
+            .read_untracked()
+            .get(&name)
+            .copied()
+            .unwrap_or_default()
+            == up
+        {
+            return;
+        }
+
+        plan.set_upgrades(name, up);
+    });
+
+    let tier = move || edited().tier();
+
+    view! {
+        <div>
+            <h2 class="text-xl">"Tower"</h2>
+            <div class="p-2 flex flex-col gap-2">
+                <div class="flex justify-between">
+                    <h2>"Damage"</h2>
+                    <Incrementor value={damage} max=MAX_UPGRADE />
+                </div>
+                <div class="flex justify-between">
+                    <h2>"Attack"</h2>
+                    <Incrementor value={attack} max=MAX_UPGRADE />
+                </div>
+                <div class="flex justify-between">
+                    <h2>"Health"</h2>
+                    <Incrementor value={health} max=MAX_UPGRADE />
+                </div>
+                <div class="flex justify-between">
+                    <h2>"Defence"</h2>
+                    <Incrementor value={defence} max=MAX_UPGRADE />
+                </div>
+                <div class="flex justify-between">
+                    <h2>"Aura"</h2>
+                    <Incrementor value={aura} max=MAX_BONUS />
+                </div>
+                <div class="flex justify-between">
+                    <h2>"Volley"</h2>
+                    <Incrementor value={volley} max=MAX_BONUS />
+                </div>
+                <h2>"Defense: "<span style:color=move || tier().color()>{move || tier().to_string()}</span></h2>
+            </div>
+        </div>
+    }
+}
+
+/// Text area for writing a note on a territory
+#[component]
+pub fn NoteEditor(terr_name: Signal<Arc<str>>, plan: Plan) -> impl IntoView {
+    let notes = plan.notes;
+
+    let note = move || {
+        notes
+            .read()
+            .get(&*terr_name.read())
+            .cloned()
+            .unwrap_or_default()
+    };
+
+    let oninput = move |text: String| {
+        let name = terr_name.get();
+
+        // typing is merged into a single edit until the text area loses focus
+        plan.set_note(Some("note"), name, text);
+    };
+
+    view! {
+        <div>
+            <h2 class="text-xl">"Notes"</h2>
+            <div class="p-2">
+                <textarea
+                    class="w-full p-1 rounded border-1 border-neutral-600"
+                    rows=3
+                    prop:value={note}
+                    on:input:target=move |ev| oninput(ev.target().value())
+                    on:change=move |_| plan.history.seal()
+                ></textarea>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,346 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::{
+    guild::Guild,
+    terr::{TerrState, Territory},
+    tower::TowerUpgrades,
+};
+
+use crate::{
+    dialog::planning::formats::PlanningModeData,
+    modes::planning::history::{Change, History, Snapshot},
+};
+
+/// The signals holding the plan currently being edited in planning mode
+#[derive(Clone, Copy)]
+pub struct Plan {
+    /// The territories the plan is made on
+    pub terrs: RwSignal<BTreeMap<Arc<str>, Territory>>,
+    /// The current territories of the map, used for plans which don't store their territories
+    pub live_terrs: RwSignal<BTreeMap<Arc<str>, Territory>>,
+    /// The guilds of the plan. The first guild is always the "nobody" guild
+    pub guilds: RwSignal<Vec<ArcRwSignal<Guild>>>,
+    /// The owner of each territory
+    pub owned: RwSignal<BTreeMap<Arc<str>, ArcRwSignal<Guild>>>,
+    /// Names of the territories which are guild hqs
+    pub hqs: RwSignal<BTreeSet<Arc<str>>>,
+    /// Notes written on territories
+    pub notes: RwSignal<BTreeMap<Arc<str>, String>>,
+    /// Tower upgrades of territories
+    pub upgrades: RwSignal<BTreeMap<Arc<str>, TowerUpgrades>>,
+    /// Undo and redo history of the plan
+    pub history: History,
+}
+
+impl Plan {
+    pub fn new() -> Self {
+        Self {
+            terrs: RwSignal::new(BTreeMap::new()),
+            live_terrs: RwSignal::new(BTreeMap::new()),
+            guilds: RwSignal::new(vec![ArcRwSignal::new(Guild::default())]),
+            owned: RwSignal::new(BTreeMap::new()),
+            hqs: RwSignal::new(BTreeSet::new()),
+            notes: RwSignal::new(BTreeMap::new()),
+            upgrades: RwSignal::new(BTreeMap::new()),
+            history: History::new(),
+        }
+    }
+
+    /// Apply changes to the plan and record them in the history
+    ///
+    /// See [`History::push`] for the meaning of the key.
+    fn change(self, key: Option<&'static str>, changes: Vec<Change>) {
+        for change in &changes {
+            change.apply(self, true);
+        }
+
+        self.history.push(key, changes);
+    }
+
+    /// Run an edit replacing large parts of the plan and record the whole plan before and after it in the history
+    fn replace(self, f: impl FnOnce()) {
+        let before = Snapshot::take(self);
+        f();
+        let after = Snapshot::take(self);
+
+        if before != after {
+            self.history.push(
+                None,
+                vec![Change::Replace(Box::new(before), Box::new(after))],
+            );
+        }
+    }
+
+    /// Give the territories to a guild and record it in the history
+    ///
+    /// Territories changing owner lose their hq status.
+    pub fn assign(
+        self,
+        key: Option<&'static str>,
+        names: impl IntoIterator<Item = Arc<str>>,
+        guild: &ArcRwSignal<Guild>,
+    ) {
+        let owned = self.owned.read_untracked();
+        let hqs = self.hqs.read_untracked();
+        let mut changes = Vec::new();
+
+        for name in names {
+            let old = owned.get(&name);
+
+            if old == Some(guild) {
+                continue;
+            }
+
+            if hqs.contains(&name) {
+                changes.push(Change::Hq(name.clone(), true, false));
+            }
+
+            changes.push(Change::Owner(name, old.cloned(), Some(guild.clone())));
+        }
+
+        drop((owned, hqs));
+        self.change(key, changes);
+    }
+
+    /// Set or remove the hq status of a territory
+    ///
+    /// A guild may only have a single hq so its other hq loses the status.
+    pub fn set_hq(self, name: Arc<str>, hq: bool) {
+        let hqs = self.hqs.read_untracked();
+
+        if hqs.contains(&name) == hq {
+            return;
+        }
+
+        let mut changes = Vec::new();
+
+        if hq {
+            let owned = self.owned.read_untracked();
+            let owner = owned.get(&name);
+
+            for other in hqs
+                .iter()
+                .filter(|t| owner.is_some() && owned.get(*t) == owner)
+            {
+                changes.push(Change::Hq(other.clone(), true, false));
+            }
+        }
+
+        changes.push(Change::Hq(name, !hq, hq));
+
+        drop(hqs);
+        self.change(None, changes);
+    }
+
+    /// Write a note on a territory, an empty note removes it
+    pub fn set_note(self, key: Option<&'static str>, name: Arc<str>, note: String) {
+        let old = self.notes.read_untracked().get(&name).cloned();
+        let new = (!note.is_empty()).then_some(note);
+
+        if old != new {
+            self.change(key, vec![Change::Note(name, old, new)]);
+        }
+    }
+
+    /// Set the tower upgrades of a territory
+    pub fn set_upgrades(self, name: Arc<str>, upgrades: TowerUpgrades) {
+        let old = self.upgrades.read_untracked().get(&name).copied();
+        let new = (!upgrades.is_default()).then_some(upgrades);
+
+        if old != new {
+            self.change(None, vec![Change::Upgrades(name, old, new)]);
+        }
+    }
+
+    pub fn add_guild(self, guild: Guild) {
+        let old = self.guilds.get_untracked();
+        let mut new = old.clone();
+        new.push(ArcRwSignal::new(guild));
+
+        self.change(None, vec![Change::Guilds(old, new)]);
+    }
+
+    /// Remove the guild at the index from the guild list, the nobody guild can't be removed
+    pub fn remove_guild(self, idx: usize) {
+        let old = self.guilds.get_untracked();
+
+        if idx == 0 || idx >= old.len() {
+            return;
+        }
+
+        let mut new = old.clone();
+        new.remove(idx);
+
+        self.change(None, vec![Change::Guilds(old, new)]);
+    }
+
+    /// Edit the guild at the index in place
+    pub fn edit_guild(self, idx: usize, f: impl FnOnce(&mut Guild)) {
+        let Some(guild) = self.guilds.read_untracked().get(idx).cloned() else {
+            return;
+        };
+
+        let old = guild.get_untracked();
+        let mut new = old.clone();
+        f(&mut new);
+
+        if old != new {
+            self.change(None, vec![Change::Guild(guild, old, new)]);
+        }
+    }
+
+    /// Subscribe the current reactive context to every part of the plan
+    pub fn track(self) {
+        self.terrs.track();
+        self.owned.track();
+        self.hqs.track();
+        self.notes.track();
+        self.upgrades.track();
+
+        for guild in self.guilds.read().iter() {
+            guild.track();
+        }
+    }
+
+    /// The owner and hq of every territory in the plan
+    pub fn state(self) -> BTreeMap<Arc<str>, TerrState> {
+        owner_state(&self.terrs.read(), &self.owned.read(), &self.hqs.read())
+    }
+
+    /// Get the current plan as data for the save formats
+    pub fn to_data(self) -> PlanningModeData {
+        PlanningModeData {
+            territories: self.terrs.get_untracked(),
+            guilds: self.guilds.get_untracked(),
+            owned_territories: self.owned.get_untracked(),
+            hqs: self.hqs.get_untracked(),
+            notes: self.notes.get_untracked(),
+            upgrades: self.upgrades.get_untracked(),
+        }
+    }
+
+    /// Replace the current plan with the given data
+    ///
+    /// Data without territories is loaded on the current territories of the map.
+    pub fn load(self, data: PlanningModeData) {
+        self.replace(|| {
+            if data.territories.is_empty() {
+                self.terrs.set(self.live_terrs.get_untracked());
+            } else {
+                self.terrs.set(data.territories);
+            }
+
+            self.guilds.set(data.guilds);
+            self.owned.set(data.owned_territories);
+            self.hqs.set(data.hqs);
+            self.notes.set(data.notes);
+            self.upgrades.set(data.upgrades);
+        });
+    }
+
+    /// Replace the ownership of the plan with the given map state
+    pub fn import_state(self, state: &BTreeMap<Arc<str>, TerrState>) {
+        self.replace(|| {
+            let nobody = ArcRwSignal::new(Guild::default());
+
+            self.guilds.set(vec![nobody.clone()]);
+            self.owned.set(
+                self.terrs
+                    .read_untracked()
+                    .keys()
+                    .map(|n| (n.clone(), nobody.clone()))
+                    .collect(),
+            );
+            self.hqs.set(BTreeSet::new());
+
+            let prefixes: BTreeSet<_> = state.values().map(|s| s.guild.prefix.clone()).collect();
+
+            for prefix in prefixes {
+                self.add_live_guild(state, &prefix);
+            }
+        });
+    }
+
+    /// Add a guild from the given map state along with its territories and hq
+    ///
+    /// A guild with the same tag already in the plan is reused.
+    pub fn import_guild(self, state: &BTreeMap<Arc<str>, TerrState>, prefix: &str) {
+        self.replace(|| self.add_live_guild(state, prefix));
+    }
+
+    fn add_live_guild(self, state: &BTreeMap<Arc<str>, TerrState>, prefix: &str) {
+        let Some(live) = state
+            .values()
+            .map(|s| &s.guild)
+            .find(|g| *g.prefix == *prefix && **g != Guild::default())
+        else {
+            return;
+        };
+
+        let existing = self
+            .guilds
+            .read_untracked()
+            .iter()
+            .skip(1)
+            .find(|g| *g.read_untracked().prefix == *prefix)
+            .cloned();
+
+        let guild = existing.unwrap_or_else(|| {
+            let guild = ArcRwSignal::new(live.clone());
+            self.guilds.update(|g| g.push(guild.clone()));
+            guild
+        });
+
+        let owned = state.iter().filter(|(_, s)| *s.guild.prefix == *prefix);
+
+        self.owned.update(|o| {
+            for (name, _) in owned.clone() {
+                o.insert(name.clone(), guild.clone());
+            }
+        });
+
+        if let Some((hq, _)) = owned.clone().find(|(_, s)| s.hq) {
+            let owners = self.owned.read_untracked();
+
+            self.hqs.update(|h| {
+                // a guild may only have a single hq
+                h.retain(|t| owners.get(t) != Some(&guild));
+                h.insert(hq.clone());
+            });
+        }
+    }
+}
+
+/// Build the map state of a plan from the owners and hqs of its territories
+///
+/// Territories without an owner belong to nobody.
+pub fn owner_state(
+    terrs: &BTreeMap<Arc<str>, Territory>,
+    owned: &BTreeMap<Arc<str>, ArcRwSignal<Guild>>,
+    hqs: &BTreeSet<Arc<str>>,
+) -> BTreeMap<Arc<str>, TerrState> {
+    terrs
+        .keys()
+        .map(|terr| {
+            let state = owned.get(terr).map_or_else(
+                || TerrState {
+                    guild: Guild::default(),
+                    acquired: None,
+                    ..Default::default()
+                },
+                |own| TerrState {
+                    guild: own.get(),
+                    acquired: None,
+                    hq: hqs.contains(terr),
+                    ..Default::default()
+                },
+            );
+
+            (terr.clone(), state)
+        })
+        .collect()
+}
@@ -0,0 +1,485 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use gloo_storage::{LocalStorage, Storage};
+use leptos::prelude::*;
+use leptos_use::core::ConnectionReadyState;
+use web_sys::{js_sys::Math, wasm_bindgen::JsValue};
+use wynnmap_types::{
+    guild::Guild,
+    session::{
+        NOBODY_ID, Participant, Presence, SessionClientMessage, SessionOp, SessionPlan,
+        SessionServerMessage,
+    },
+    terr::Territory,
+};
+
+use crate::{
+    datasource,
+    modes::planning::{plan::Plan, slots::PlanSlots},
+    wynnmap::highlight::HighlightView,
+};
+
+/// Local storage key of the name shown to other participants
+const NAME_KEY: &str = "session_name";
+/// Url query parameter holding the session room id
+pub const SESSION_PARAM: &str = "session";
+
+/// Highlight colors of the other participants
+const PARTICIPANT_COLORS: [&str; 6] = [
+    "#3b82f6", "#ec4899", "#14b8a6", "#eab308", "#a855f7", "#f97316",
+];
+
+/// Highlight color of a participant
+pub const fn participant_color(id: u32) -> &'static str {
+    PARTICIPANT_COLORS[id as usize % PARTICIPANT_COLORS.len()]
+}
+
+/// A random id which is never the id of the nobody guild
+fn random_id() -> u64 {
+    (Math::random() * 2f64.powi(53)) as u64 + 1
+}
+
+/// A random room id made of url safe characters
+fn random_room() -> Arc<str> {
+    const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    (0..10)
+        .map(|_| CHARS[(Math::random() * CHARS.len() as f64) as usize % CHARS.len()] as char)
+        .collect::<String>()
+        .into()
+}
+
+/// State of a collaborative planning session
+#[derive(Clone, Copy)]
+pub struct Session {
+    /// Id of the session room, if in a session
+    pub room: RwSignal<Option<Arc<str>>>,
+    /// Name shown to the other participants
+    pub name: RwSignal<String>,
+    /// Own participant id once joined
+    pub you: RwSignal<Option<u32>>,
+    /// The other participants of the session
+    pub participants: RwSignal<BTreeMap<u32, Participant>>,
+    /// The plan as last sent to or received from the session, none until the session has been joined
+    synced: StoredValue<Option<SessionPlan>>,
+    /// Session ids of the guilds of the plan apart from the nobody guild
+    ids: StoredValue<Vec<(u64, ArcRwSignal<Guild>)>>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self {
+            room: RwSignal::new(None),
+            name: RwSignal::new(
+                LocalStorage::get(NAME_KEY).unwrap_or_else(|_| String::from("Anonymous")),
+            ),
+            you: RwSignal::new(None),
+            participants: RwSignal::new(BTreeMap::new()),
+            synced: StoredValue::new(None),
+            ids: StoredValue::new(Vec::new()),
+        }
+    }
+
+    /// Start a new session with the current plan
+    pub fn start(self, slots: PlanSlots) {
+        let room = random_room();
+
+        slots.bind_room(room.clone());
+        self.set_room(Some(room));
+    }
+
+    /// Join an existing session opening the plan used for it before or a new plan
+    pub fn join(
+        self,
+        plan: Plan,
+        slots: PlanSlots,
+        room: Arc<str>,
+        terrs: BTreeMap<Arc<str>, Territory>,
+    ) {
+        slots.open_room(plan, room.clone(), terrs);
+        self.set_room(Some(room));
+    }
+
+    pub fn leave(self) {
+        self.set_room(None);
+    }
+
+    pub fn set_name(self, name: String) {
+        let _ = LocalStorage::set(NAME_KEY, &name);
+        self.name.set(name);
+    }
+
+    /// Link for inviting others to the session
+    pub fn link(self) -> Option<String> {
+        let room = self.room.get()?;
+
+        Some(format!(
+            "{}/plan?{SESSION_PARAM}={room}",
+            window().location().origin().unwrap_or_default()
+        ))
+    }
+
+    fn set_room(self, room: Option<Arc<str>>) {
+        // keep the session in the url so that reloading the page rejoins it
+        let url = room.as_ref().map_or_else(
+            || String::from("/plan"),
+            |r| format!("/plan?{SESSION_PARAM}={r}"),
+        );
+        let _ = window()
+            .history()
+            .and_then(|h| h.replace_state_with_url(&JsValue::NULL, "", Some(&url)));
+
+        self.you.set(None);
+        self.participants.set(BTreeMap::new());
+        self.synced.set_value(None);
+        self.room.set(room);
+    }
+
+    /// Convert the plan into the session representation giving new guilds ids
+    fn to_session(self, plan: Plan) -> SessionPlan {
+        let guilds = plan.guilds.read_untracked();
+        let Some((nobody, others)) = guilds.split_first() else {
+            return SessionPlan::default();
+        };
+
+        self.ids.update_value(|ids| {
+            ids.retain(|(_, s)| others.contains(s));
+
+            for guild in others {
+                if !ids.iter().any(|(_, s)| s == guild) {
+                    ids.push((random_id(), guild.clone()));
+                }
+            }
+        });
+
+        let ids = self.ids.read_value();
+        let id_of = |g: &ArcRwSignal<Guild>| {
+            if g == nobody {
+                Some(NOBODY_ID)
+            } else {
+                ids.iter().find(|(_, s)| s == g).map(|(id, _)| *id)
+            }
+        };
+
+        SessionPlan {
+            guilds: guilds
+                .iter()
+                .filter_map(|g| Some((id_of(g)?, g.get_untracked())))
+                .collect(),
+            owners: plan
+                .owned
+                .read_untracked()
+                .iter()
+                .filter_map(|(t, g)| Some((t.clone(), id_of(g)?)))
+                .filter(|(_, id)| *id != NOBODY_ID)
+                .collect(),
+            hqs: plan.hqs.get_untracked(),
+            notes: plan.notes.get_untracked(),
+            upgrades: plan.upgrades.get_untracked(),
+        }
+    }
+
+    /// Replace the plan with the session representation keeping the existing guild signals
+    fn write_plan(self, plan: Plan, data: &SessionPlan) {
+        let nobody = plan
+            .guilds
+            .read_untracked()
+            .first()
+            .cloned()
+            .unwrap_or_default();
+
+        let guilds: Vec<_> = self
+            .ids
+            .try_update_value(|ids| {
+                data.guilds
+                    .iter()
+                    .map(|(id, guild)| {
+                        let signal = if *id == NOBODY_ID {
+                            nobody.clone()
+                        } else if let Some((_, s)) = ids.iter().find(|(i, _)| i == id) {
+                            s.clone()
+                        } else {
+                            let s = ArcRwSignal::new(guild.clone());
+                            ids.push((*id, s.clone()));
+                            s
+                        };
+
+                        if *signal.read_untracked() != *guild {
+                            signal.set(guild.clone());
+                        }
+
+                        signal
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let owned: BTreeMap<_, _> = data
+            .owners
+            .iter()
+            .filter_map(|(t, id)| {
+                let idx = data.guilds.iter().position(|(g, _)| g == id)?;
+                Some((t.clone(), guilds[idx].clone()))
+            })
+            .collect();
+
+        // only notify the parts of the plan which changed
+        if *plan.guilds.read_untracked() != guilds {
+            plan.guilds.set(guilds);
+        }
+
+        let current_owned: BTreeMap<_, _> = plan
+            .owned
+            .read_untracked()
+            .iter()
+            .filter(|(_, g)| **g != nobody)
+            .map(|(t, g)| (t.clone(), g.clone()))
+            .collect();
+        if current_owned != owned {
+            plan.owned.set(owned);
+        }
+
+        if *plan.hqs.read_untracked() != data.hqs {
+            plan.hqs.set(data.hqs.clone());
+        }
+
+        if *plan.notes.read_untracked() != data.notes {
+            plan.notes.set(data.notes.clone());
+        }
+
+        if *plan.upgrades.read_untracked() != data.upgrades {
+            plan.upgrades.set(data.upgrades.clone());
+        }
+    }
+
+    /// Send the local changes of the plan to the session
+    fn send_changes(self, plan: Plan, send: &dyn Fn(&SessionClientMessage)) {
+        let current = self.to_session(plan);
+        let ops = self
+            .synced
+            .with_value(|s| s.clone().unwrap_or_default().diff(&current));
+
+        for op in ops {
+            send(&SessionClientMessage::Op(op));
+        }
+
+        self.synced.set_value(Some(current));
+    }
+
+    /// Update the synced plan with changes from the session keeping local changes which haven't been sent yet
+    ///
+    /// The local changes stay pending and are sent with the next changes.
+    fn rebase(self, plan: Plan, update: impl FnOnce(&mut Option<SessionPlan>)) {
+        let local = self.to_session(plan);
+        let pending = self
+            .synced
+            .with_value(|s| s.as_ref().map(|s| s.diff(&local)))
+            .unwrap_or_default();
+
+        self.synced.update_value(update);
+
+        let mut rebased = self.synced.get_value().unwrap_or_default();
+        for op in &pending {
+            rebased.apply(op);
+        }
+
+        self.write_plan(plan, &rebased);
+    }
+
+    /// Apply an operation received from the session
+    fn receive_op(self, plan: Plan, op: &SessionOp) {
+        self.rebase(plan, |s| {
+            if let Some(s) = s {
+                s.apply(op);
+            }
+        });
+    }
+
+    fn receive(self, plan: Plan, msg: &SessionServerMessage) {
+        match msg {
+            SessionServerMessage::Welcome(you, data, participants) => {
+                let local = self.to_session(plan);
+
+                // edits made while reconnecting are applied on top of the plan of the session
+                self.rebase(plan, |s| *s = Some(data.clone()));
+
+                // the history can't undo changes made by others
+                if self.to_session(plan) != local {
+                    plan.history.clear();
+                }
+
+                self.you.set(Some(*you));
+                self.participants
+                    .set(participants.iter().map(|p| (p.id, p.clone())).collect());
+            }
+            SessionServerMessage::Op(_, op) => self.receive_op(plan, op),
+            SessionServerMessage::Joined(participant) => {
+                if Some(participant.id) != self.you.get_untracked() {
+                    self.participants
+                        .update(|p| _ = p.insert(participant.id, participant.clone()));
+                }
+            }
+            SessionServerMessage::Left(id) => {
+                self.participants.update(|p| _ = p.remove(id));
+            }
+            SessionServerMessage::Presence(id, presence) => {
+                if Some(*id) != self.you.get_untracked() {
+                    self.participants.update(|p| {
+                        if let Some(p) = p.get_mut(id) {
+                            p.presence = presence.clone();
+                        }
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Connection to a session room syncing the plan and the presence of the participants
+#[component]
+pub fn SessionConnection(
+    session: Session,
+    room: Arc<str>,
+    plan: Plan,
+    #[prop(into)] selected: Signal<Option<Arc<str>>>,
+    #[prop(into)] selection: Signal<BTreeSet<Arc<str>>>,
+) -> impl IntoView {
+    let (ready_state, send) = datasource::ws_session(&room, move |msg| session.receive(plan, msg));
+
+    // join whenever the connection opens, rejoining replaces the plan with the plan of the session and the edits made
+    // while disconnected
+    Effect::new({
+        let send = send.clone();
+        move || match ready_state.get() {
+            ConnectionReadyState::Open => send(&SessionClientMessage::Join(
+                Arc::from(session.name.get_untracked()),
+                session.to_session(plan),
+            )),
+            _ => session.you.set(None),
+        }
+    });
+
+    // send local edits once joined
+    Effect::new({
+        let send = send.clone();
+        move || {
+            plan.track();
+
+            if session.you.get().is_some() {
+                session.send_changes(plan, &*send);
+            }
+        }
+    });
+
+    Effect::new(move || {
+        let presence = Presence {
+            selected: selected.get(),
+            selection: selection.get(),
+        };
+
+        if session.you.get().is_some() {
+            send(&SessionClientMessage::Presence(presence));
+        }
+    });
+}
+
+/// Map layers highlighting the territories selected by the other participants
+#[component]
+pub fn SessionPresenceView(
+    session: Session,
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+) -> impl IntoView {
+    view! {
+        <For
+            each=move || session.participants.get().into_keys()
+            key=|id| *id
+            children=move |id| {
+                let highlighted = Signal::derive(move || {
+                    session
+                        .participants
+                        .read()
+                        .get(&id)
+                        .map(|p| {
+                            let mut terrs = p.presence.selection.clone();
+                            terrs.extend(p.presence.selected.clone());
+                            terrs
+                        })
+                        .unwrap_or_default()
+                });
+
+                view! { <HighlightView terrs={terrs} highlighted={highlighted} color=participant_color(id) /> }
+            }
+        />
+    }
+}
+
+/// Sidebar controls for starting, joining and leaving sessions
+#[component]
+pub fn SessionPanel(session: Session, slots: PlanSlots) -> impl IntoView {
+    let copy_link = move |_| {
+        if let Some(link) = session.link() {
+            let _promise = window().navigator().clipboard().write_text(&link);
+        }
+    };
+
+    let status = move || match (session.room.get(), session.you.get()) {
+        (None, _) => "Not in a session",
+        (Some(_), None) => "Connecting...",
+        (Some(_), Some(_)) => "Connected",
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <h2>"Live session"</h2>
+            <p class="text-sm text-neutral-400">{status}</p>
+
+            <label class="flex flex-col text-base">
+                "Your name:"
+                <input
+                    type="text"
+                    class="p-1 rounded border-1 border-neutral-600"
+                    prop:value={move || session.name.get()}
+                    on:change:target=move |ev| session.set_name(ev.target().value())
+                />
+            </label>
+
+            <Show
+                when={move || session.room.read().is_some()}
+                fallback={move || view! {
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| session.start(slots)}>
+                        "Start session"
+                    </button>
+                }}
+            >
+                <div class="flex gap-2">
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg flex gap-1" on:click={copy_link}>
+                        "Copy invite link"
+                        <icons::Clipboard size=24/>
+                    </button>
+                    <button class="p-1 px-2 border-1 border-red-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| session.leave()}>
+                        "Leave"
+                    </button>
+                </div>
+
+                <div class="flex flex-col text-base">
+                    <For
+                        each=move || session.participants.get().into_values()
+                        key=|p| p.clone()
+                        children=move |p| {
+                            view! {
+                                <span class="flex items-center gap-1">
+                                    <span class="inline-block size-3 rounded-sm" style:background-color=participant_color(p.id)></span>
+                                    {p.name.to_string()}
+                                    <span class="text-neutral-400 truncate">{p.presence.selected.map(|s| s.to_string())}</span>
+                                </span>
+                            }
+                        }
+                    />
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,273 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use gloo_storage::{LocalStorage, Storage};
+use jiff::Timestamp;
+use leptos::{logging::error, prelude::*};
+use serde::{Deserialize, Serialize};
+use wynnmap_types::terr::Territory;
+
+use crate::{
+    dialog::planning::formats::{DataConvert, PlanningModeData, wynnmap::WynnmapData},
+    modes::planning::plan::Plan,
+};
+
+/// Local storage key of the list of saved plans
+const INDEX_KEY: &str = "plans";
+/// Local storage key of the id of the last opened plan
+const LAST_KEY: &str = "lastplan";
+
+/// Local storage key of the data of a plan
+fn plan_key(id: u64) -> String {
+    format!("plan:{id}")
+}
+
+/// A plan saved in local storage
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlanSlot {
+    pub id: u64,
+    pub name: String,
+    /// When the plan was last saved
+    pub updated: Timestamp,
+    /// Id of the session room the plan is used for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room: Option<Arc<str>>,
+}
+
+/// The named plans saved in local storage
+#[derive(Clone, Copy)]
+pub struct PlanSlots {
+    pub slots: RwSignal<Vec<PlanSlot>>,
+    /// Id of the plan currently being edited
+    pub current: RwSignal<Option<u64>>,
+}
+
+impl PlanSlots {
+    /// Read the list of saved plans from local storage
+    pub fn load() -> Self {
+        let slots: Vec<PlanSlot> = LocalStorage::get(INDEX_KEY).unwrap_or_default();
+
+        Self {
+            slots: RwSignal::new(slots),
+            current: RwSignal::new(None),
+        }
+    }
+
+    /// Open the plan which was open last or create a new plan if there is none
+    pub fn restore(self, plan: Plan) {
+        let last: Option<u64> = LocalStorage::get(LAST_KEY).ok();
+
+        let id = last
+            .filter(|id| self.slots.read_untracked().iter().any(|s| s.id == *id))
+            .or_else(|| self.slots.read_untracked().first().map(|s| s.id));
+
+        if let Some(id) = id {
+            self.open(plan, id);
+        } else {
+            self.create(plan, String::from("Untitled plan"), BTreeMap::new());
+        }
+    }
+
+    /// Name of the plan currently being edited
+    pub fn current_name(self) -> Option<String> {
+        let current = self.current.get()?;
+
+        self.slots
+            .read()
+            .iter()
+            .find(|s| s.id == current)
+            .map(|s| s.name.clone())
+    }
+
+    /// Save the plan into the current slot
+    pub fn save(self, plan: Plan) {
+        let Some(id) = self.current.get_untracked() else {
+            return;
+        };
+
+        let data = WynnmapData::from_data(&plan.to_data());
+
+        if let Err(err) = LocalStorage::set(plan_key(id), data) {
+            error!("Failed to save plan: {err}");
+            return;
+        }
+
+        self.slots.update(|slots| {
+            if let Some(slot) = slots.iter_mut().find(|s| s.id == id) {
+                slot.updated = Timestamp::now();
+            }
+        });
+        self.write_index();
+    }
+
+    /// Switch to the plan with the given id saving the current plan first
+    pub fn open(self, plan: Plan, id: u64) {
+        if self.current.get_untracked() == Some(id) {
+            return;
+        }
+
+        self.save(plan);
+
+        let data = LocalStorage::get::<WynnmapData>(plan_key(id))
+            .map(DataConvert::to_data)
+            .unwrap_or_else(|_| empty_plan(BTreeMap::new()));
+
+        self.current.set(Some(id));
+        plan.load(data);
+        plan.history.clear();
+
+        let _ = LocalStorage::set(LAST_KEY, id);
+    }
+
+    /// Read the data of a saved plan without opening it
+    pub fn read(self, id: u64) -> Option<PlanningModeData> {
+        LocalStorage::get::<WynnmapData>(plan_key(id))
+            .map(DataConvert::to_data)
+            .ok()
+    }
+
+    /// Create a new empty plan on the given territories and switch to it
+    pub fn create(self, plan: Plan, name: String, terrs: BTreeMap<Arc<str>, Territory>) {
+        self.save(plan);
+
+        let id = self.new_id();
+        self.slots.update(|s| {
+            s.push(PlanSlot {
+                id,
+                name,
+                updated: Timestamp::now(),
+                room: None,
+            });
+        });
+
+        self.current.set(Some(id));
+        plan.load(empty_plan(terrs));
+        plan.history.clear();
+
+        self.save(plan);
+        let _ = LocalStorage::set(LAST_KEY, id);
+    }
+
+    /// Create a copy of a saved plan
+    pub fn duplicate(self, plan: Plan, id: u64) {
+        // make sure the copy contains the latest changes
+        if self.current.get_untracked() == Some(id) {
+            self.save(plan);
+        }
+
+        let Some(slot) = self
+            .slots
+            .read_untracked()
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+        else {
+            return;
+        };
+        let Ok(data) = LocalStorage::get::<WynnmapData>(plan_key(id)) else {
+            return;
+        };
+
+        let new_id = self.new_id();
+        if let Err(err) = LocalStorage::set(plan_key(new_id), data) {
+            error!("Failed to save plan: {err}");
+            return;
+        }
+
+        self.slots.update(|s| {
+            s.push(PlanSlot {
+                id: new_id,
+                name: format!("{} (copy)", slot.name),
+                updated: Timestamp::now(),
+                room: None,
+            });
+        });
+        self.write_index();
+    }
+
+    /// Switch to the plan used for a session room creating it if there is none
+    pub fn open_room(self, plan: Plan, room: Arc<str>, terrs: BTreeMap<Arc<str>, Territory>) {
+        let existing = self
+            .slots
+            .read_untracked()
+            .iter()
+            .find(|s| s.room.as_ref() == Some(&room))
+            .map(|s| s.id);
+
+        match existing {
+            Some(id) => self.open(plan, id),
+            None => {
+                self.create(plan, format!("Session {room}"), terrs);
+                self.bind_room(room);
+            }
+        }
+    }
+
+    /// Use the current plan for a session room
+    pub fn bind_room(self, room: Arc<str>) {
+        let Some(id) = self.current.get_untracked() else {
+            return;
+        };
+
+        self.slots.update(|slots| {
+            for slot in slots.iter_mut() {
+                if slot.room.as_ref() == Some(&room) {
+                    slot.room = None;
+                }
+            }
+
+            if let Some(slot) = slots.iter_mut().find(|s| s.id == id) {
+                slot.room = Some(room);
+            }
+        });
+        self.write_index();
+    }
+
+    pub fn rename(self, id: u64, name: String) {
+        self.slots.update(|slots| {
+            if let Some(slot) = slots.iter_mut().find(|s| s.id == id) {
+                slot.name = name;
+            }
+        });
+        self.write_index();
+    }
+
+    /// Delete a saved plan switching to another plan if it is currently open
+    pub fn delete(self, plan: Plan, id: u64) {
+        LocalStorage::delete(plan_key(id));
+        self.slots.update(|s| s.retain(|s| s.id != id));
+        self.write_index();
+
+        if self.current.get_untracked() == Some(id) {
+            let terrs = plan.terrs.get_untracked();
+            self.current.set(None);
+
+            match self.slots.read_untracked().first().map(|s| s.id) {
+                Some(next) => self.open(plan, next),
+                None => self.create(plan, String::from("Untitled plan"), terrs),
+            }
+        }
+    }
+
+    /// Generate an unused plan id
+    fn new_id(self) -> u64 {
+        let now = Timestamp::now().as_millisecond().unsigned_abs();
+        let max = self.slots.read_untracked().iter().map(|s| s.id).max();
+
+        max.map_or(now, |m| now.max(m + 1))
+    }
+
+    fn write_index(self) {
+        if let Err(err) = LocalStorage::set(INDEX_KEY, &*self.slots.read_untracked()) {
+            error!("Failed to save plan list: {err}");
+        }
+    }
+}
+
+/// A plan without any guilds or owners on the given territories
+fn empty_plan(terrs: BTreeMap<Arc<str>, Territory>) -> PlanningModeData {
+    PlanningModeData {
+        territories: terrs,
+        guilds: vec![ArcRwSignal::new(Default::default())],
+        ..Default::default()
+    }
+}
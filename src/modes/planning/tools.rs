@@ -0,0 +1,283 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use leptos::prelude::*;
+use wynnmap_types::{graph::TerrGraph, guild::Guild};
+
+use crate::{modes::planning::plan::Plan, wynnmap::MapDrag};
+
+/// Minimum distance between the points of a lasso
+const LASSO_STEP: i32 = 8;
+
+/// Tools for editing the plan directly on the map
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Tool {
+    /// Clicking selects territories and dragging moves the map
+    #[default]
+    Select,
+    /// Dragging gives every territory passed over to the active guild
+    Brush,
+    /// Dragging selects the territories within a rectangle
+    Rectangle,
+    /// Dragging selects the territories within a freely drawn shape
+    Lasso,
+    /// Clicking gives the connected territories with the same owner to the active guild
+    Fill,
+}
+
+impl Tool {
+    pub const ALL: [Self; 5] = [
+        Self::Select,
+        Self::Brush,
+        Self::Rectangle,
+        Self::Lasso,
+        Self::Fill,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Select => "Select",
+            Self::Brush => "Brush",
+            Self::Rectangle => "Rectangle select",
+            Self::Lasso => "Lasso select",
+            Self::Fill => "Flood fill",
+        }
+    }
+}
+
+/// State of the planning tools
+#[derive(Clone, Copy)]
+pub struct Tools {
+    pub tool: RwSignal<Tool>,
+    /// Index of the guild given territories by the tools
+    pub guild: RwSignal<usize>,
+    /// Territories selected using the rectangle or lasso
+    pub selection: RwSignal<BTreeSet<Arc<str>>>,
+    /// Corners of the selection currently being drawn
+    pub shape: RwSignal<Vec<[i32; 2]>>,
+}
+
+impl Tools {
+    pub fn new() -> Self {
+        Self {
+            tool: RwSignal::new(Tool::Select),
+            guild: RwSignal::new(0),
+            selection: RwSignal::new(BTreeSet::new()),
+            shape: RwSignal::new(Vec::new()),
+        }
+    }
+
+    /// Handle a drag on the map returning whether the current tool uses the drag
+    pub fn drag(self, plan: Plan, drag: MapDrag) -> bool {
+        match (self.tool.get_untracked(), drag) {
+            (Tool::Select, _) => false,
+            (Tool::Brush, MapDrag::Start(pos)) => {
+                // every stroke is a separate history entry
+                plan.history.seal();
+                self.paint(plan, pos);
+                true
+            }
+            (Tool::Brush, MapDrag::Move(pos)) => {
+                self.paint(plan, pos);
+                true
+            }
+            (Tool::Brush, MapDrag::End) => {
+                plan.history.seal();
+                true
+            }
+            (Tool::Rectangle | Tool::Lasso, MapDrag::Start(pos)) => {
+                self.shape.set(vec![pos]);
+                true
+            }
+            (Tool::Rectangle, MapDrag::Move(pos)) => {
+                self.shape.update(|s| {
+                    let Some(&[x, y]) = s.first() else { return };
+                    *s = vec![[x, y], [pos[0], y], pos, [x, pos[1]]];
+                });
+                true
+            }
+            (Tool::Lasso, MapDrag::Move(pos)) => {
+                self.shape.update(|s| {
+                    if s.last()
+                        .is_none_or(|l| (l[0] - pos[0]).abs() + (l[1] - pos[1]).abs() >= LASSO_STEP)
+                    {
+                        s.push(pos);
+                    }
+                });
+                true
+            }
+            (Tool::Rectangle | Tool::Lasso, MapDrag::End) => {
+                let shape = self.shape.try_update(std::mem::take).unwrap_or_default();
+
+                self.selection.set(
+                    plan.terrs
+                        .read_untracked()
+                        .iter()
+                        .filter(|(_, t)| in_polygon(&shape, t.location.get_midpoint()))
+                        .map(|(n, _)| n.clone())
+                        .collect(),
+                );
+                true
+            }
+            (Tool::Fill, MapDrag::Start(pos)) => {
+                self.fill(plan, pos);
+                true
+            }
+            (Tool::Fill, _) => true,
+        }
+    }
+
+    /// The guild given territories by the tools
+    fn active_guild(self, plan: Plan) -> Option<ArcRwSignal<Guild>> {
+        plan.guilds
+            .read_untracked()
+            .get(self.guild.get_untracked())
+            .cloned()
+    }
+
+    /// Give the territory at the position to the active guild
+    fn paint(self, plan: Plan, pos: [i32; 2]) {
+        let name = plan
+            .terrs
+            .read_untracked()
+            .iter()
+            .find(|(_, t)| t.location.contains(pos))
+            .map(|(n, _)| n.clone());
+
+        if let Some(name) = name
+            && let Some(guild) = self.active_guild(plan)
+        {
+            plan.assign(Some("brush"), [name], &guild);
+        }
+    }
+
+    /// Give the region of connected territories sharing the owner of the territory at the position to the active
+    /// guild
+    fn fill(self, plan: Plan, pos: [i32; 2]) {
+        let Some(guild) = self.active_guild(plan) else {
+            return;
+        };
+
+        let region = {
+            let terrs = plan.terrs.read_untracked();
+            let owned = plan.owned.read_untracked();
+            let nobody = plan.guilds.read_untracked().first().cloned();
+
+            let Some(start) = terrs
+                .iter()
+                .find(|(_, t)| t.location.contains(pos))
+                .map(|(n, _)| n.clone())
+            else {
+                return;
+            };
+
+            // territories without an owner entry belong to nobody
+            let owner_of = |n: &Arc<str>| owned.get(n).cloned().or_else(|| nobody.clone());
+            let target = owner_of(&start);
+
+            TerrGraph::filtered(&terrs, |n| owner_of(n) == target)
+                .distances(&start)
+                .into_keys()
+                .collect::<Vec<_>>()
+        };
+
+        plan.assign(None, region, &guild);
+    }
+
+    /// Give the selected territories to the guild with the given index
+    pub fn assign_selection(self, plan: Plan, idx: usize) {
+        if let Some(guild) = plan.guilds.read_untracked().get(idx) {
+            plan.assign(None, self.selection.get_untracked(), guild);
+        }
+    }
+}
+
+/// Check whether the point is inside the polygon using ray casting
+fn in_polygon(polygon: &[[i32; 2]], [px, py]: [i32; 2]) -> bool {
+    let (px, py) = (f64::from(px), f64::from(py));
+    let mut inside = false;
+
+    for (i, a) in polygon.iter().enumerate() {
+        let b = polygon[(i + 1) % polygon.len()];
+        let [ax, ay] = a.map(f64::from);
+        let [bx, by] = b.map(f64::from);
+
+        if (ay > py) != (by > py) && px < (bx - ax) * (py - ay) / (by - ay) + ax {
+            inside = !inside;
+        }
+    }
+
+    inside
+}
+
+/// Sidebar controls for picking the tool and the guild used by the tools
+#[component]
+pub fn ToolBar(tools: Tools, plan: Plan) -> impl IntoView {
+    let Tools {
+        tool,
+        guild,
+        selection,
+        ..
+    } = tools;
+
+    let tool_button = move |t: Tool| {
+        let icon = match t {
+            Tool::Select => view! { <icons::MousePointer2 size=24/> }.into_any(),
+            Tool::Brush => view! { <icons::Brush size=24/> }.into_any(),
+            Tool::Rectangle => view! { <icons::SquareDashed size=24/> }.into_any(),
+            Tool::Lasso => view! { <icons::Lasso size=24/> }.into_any(),
+            Tool::Fill => view! { <icons::PaintBucket size=24/> }.into_any(),
+        };
+
+        view! {
+            <button
+                class="p-2 border-neutral-600 border rounded-md hover:bg-neutral-700"
+                class:bg-neutral-700={move || tool.get() == t}
+                title={t.name()}
+                on:click={move |_| tool.set(t)}
+            >
+                {icon}
+            </button>
+        }
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 mx-2">
+            <div class="flex gap-2">
+                {Tool::ALL.map(tool_button)}
+            </div>
+
+            <select class="p-1 rounded border-1 border-neutral-600" on:input:target=move |ev| {
+                if let Ok(idx) = ev.target().value().parse() {
+                    guild.set(idx);
+                }
+            }>
+                <ForEnumerate
+                    each=move || plan.guilds.get()
+                    key=|g| g.get()
+                    children=move |idx, g| {
+                        view! {
+                            <option value={idx} selected={move || guild.get() == idx.get()}>
+                                {g.get().name} " ["{g.get().prefix}"]"
+                            </option>
+                        }
+                    }
+                />
+            </select>
+
+            <div class="flex flex-col gap-1" class:hidden={move || selection.read().is_empty()}>
+                <p>{move || format!("{} territories selected", selection.read().len())}</p>
+                <div class="flex gap-2">
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| tools.assign_selection(plan, guild.get())}>
+                        "Assign"
+                    </button>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| tools.assign_selection(plan, 0)}>
+                        "Clear"
+                    </button>
+                    <button class="p-1 px-2 border-1 border-neutral-600 hover:bg-neutral-700 rounded-lg" on:click={move |_| selection.set(BTreeSet::new())}>
+                        "Deselect"
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
@@ -7,7 +7,7 @@ use std::{
 use leptos::prelude::*;
 use wynnmap_types::{
     terr::{TerrState, Territory},
-    tier::WynnTier,
+    tower::{TowerUpgrades, stat_multiplier},
 };
 
 use crate::components::{checkbox::Checkbox, incrementor::Incrementor};
@@ -76,33 +76,20 @@ pub fn TerrCalc(
     let max_externs = Memo::new(move |_| ext_names.read().len() as i32);
 
     // calculate a stat based on the current values
-    let calc_stat = move |val: f64| {
-        if hq.get() {
-            val * (0.25f64.mul_add(f64::from(externs.get()), 1.5))
-                * (0.3f64.mul_add(f64::from(conns.get()), 1.0))
-        } else {
-            val * (0.3f64.mul_add(f64::from(conns.get()), 1.0))
+    let calc_stat = move |val: f64| val * stat_multiplier(hq.get(), conns.get(), externs.get());
+
+    let def_tier = move || {
+        TowerUpgrades {
+            damage: damage.get() as u8,
+            attack: attacks.get() as u8,
+            health: health.get() as u8,
+            defence: defense.get() as u8,
+            aura: aura.get() as u8,
+            volley: volley.get() as u8,
         }
+        .tier()
     };
 
-    let def_num = Memo::new(move |_| {
-        let x =
-            damage.get() + attacks.get() + health.get() + defense.get() + aura.get() + volley.get();
-
-        let mut x = x as i32;
-
-        if aura.get() == 0 {
-            x -= 5;
-        }
-        if volley.get() == 0 {
-            x -= 3;
-        }
-
-        x
-    });
-
-    let def_tier = move || WynnTier::from_defnum(def_num.get());
-
     view! {
         <div>
             <h2 class="text-xl">"Tower"</h2>
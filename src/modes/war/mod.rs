@@ -1,23 +1,44 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    time::Duration,
+};
 
 use jiff::SignedDuration;
 use leptos::{prelude::*, task::spawn_local};
-use wynnmap_types::terr::TerrTimestamps;
+use wynnmap_types::{routing::TradeRoutes, terr::TerrTimestamps};
 
 use crate::{
     components::{
+        attackroute::{AttackPlanner, AttackRoutePanel},
+        capturelog::CaptureLog,
         checkbox::Checkbox,
+        focus::use_focus,
+        frontline::{Frontline, FrontlinePanel, FrontlineView},
         gleaderboard::Gleaderboard,
+        highlight::HighlightPanel,
         sidebar::Sidebar,
-        sidecard::{SideCard, terr::TerrStats},
+        sidecard::{SideCard, routes::RouteInfo, terr::TerrStats},
+        watch::{WatchPanel, Watcher},
     },
     datasource,
-    dialog::{Dialogs, info::info_dialog},
+    dialog::{
+        Dialogs, guild::provide_guild_profiles, info::info_dialog, palette::CommandPalette,
+        terrtable::terr_table,
+    },
     modes::war::calc::TerrCalc,
     sectimer::SecondTimer,
     settings::use_toggle,
+    urlstate::{UrlState, use_url_state},
     util::fmt_time_short,
-    wynnmap::{WynnMap, conns::Connections, maptile::WithDefaultMapTiles, terrs::TerrView},
+    wynnmap::{
+        WynnMap,
+        colormode::{ColorLegend, ColorMode, ColorModeSelect},
+        conns::Connections,
+        highlight::HighlightView,
+        maptile::WithDefaultMapTiles,
+        routes::TradeRouteView,
+        terrs::TerrView,
+    },
 };
 
 mod calc;
@@ -31,6 +52,9 @@ pub fn WarMap() -> impl IntoView {
     let show_res = use_toggle("resico", true);
     let show_timers = use_toggle("timers", true);
     let show_guild_leaderboard = use_toggle("gleaderboard", true);
+    let show_routes = use_toggle("traderoutes", false);
+
+    let color_mode = RwSignal::new(ColorMode::default());
 
     let terrs = RwSignal::new(BTreeMap::new());
     let state = RwSignal::new(BTreeMap::new());
@@ -81,11 +105,54 @@ pub fn WarMap() -> impl IntoView {
 
     spawn_local(load_owners());
 
-    datasource::ws_terr_updates(state, last_updated);
+    let captures = RwSignal::new(VecDeque::new());
+    datasource::ws_terr_updates(state, last_updated, captures);
 
     let hovered = RwSignal::new(None);
     let selected = RwSignal::new(None);
 
+    provide_guild_profiles(terrs, state, selected);
+
+    let frontline = Frontline::new(terrs.into(), state.into());
+    let planner = AttackPlanner::new(terrs.into(), state.into());
+
+    // tags of the guilds whose territories are highlighted
+    let highlighted_guilds = RwSignal::new(BTreeSet::new());
+    let highlighted = Memo::new(move |_| {
+        let guilds = highlighted_guilds.read();
+
+        state
+            .read()
+            .iter()
+            .filter(|(_, s)| guilds.contains(&s.guild.prefix))
+            .map(|(n, _)| n.clone())
+            .collect::<BTreeSet<_>>()
+    });
+
+    let focus = use_focus();
+
+    use_url_state(UrlState {
+        selected: Some(selected),
+        guilds: Some(highlighted_guilds),
+        layers: vec![
+            ("terrs", show_terrs),
+            ("conns", show_conns),
+            ("resico", show_res),
+            ("timers", show_timers),
+            ("traderoutes", show_routes),
+        ],
+        ..Default::default()
+    });
+
+    // trade routes of the guild owning the selected territory
+    let routes = Memo::new(move |_| {
+        let sel = selected.get()?;
+        let state = state.read();
+        let prefix = state.get(&sel)?.guild.prefix.clone();
+
+        TradeRoutes::for_guild(&terrs.read(), &state, &prefix)
+    });
+
     let SecondTimer(now) = expect_context();
     let data_age = Memo::new(move |_| {
         last_updated
@@ -128,15 +195,33 @@ pub fn WarMap() -> impl IntoView {
 
             // conns
             <Show when={move || show_conns.get()}>
-                <Connections terrs />
+                <Connections terrs state focus route={planner.path()} />
             </Show>
 
             // territories
             <Show when={move || show_terrs.get()}>
-                <TerrView terrs state hovered />
+                <TerrView terrs state hovered color_mode focus />
+            </Show>
+
+            <HighlightView terrs highlighted color="#facc15" />
+
+            // frontline of the picked guild
+            <FrontlineView terrs frontline />
+
+            // trade routes
+            <Show when={move || show_routes.get() && selected.read().is_some()}>
+                <TradeRouteView terrs routes selected />
             </Show>
         </WynnMap>
 
+        <CommandPalette terrs state selected />
+
+        <Watcher state />
+
+        <Show when={move || show_terrs.get()}>
+            <ColorLegend mode=color_mode />
+        </Show>
+
         // hover box
         {move || if let Some(hovered) = hovered.get() {
             if selected.get().is_some() {
@@ -175,13 +260,36 @@ pub fn WarMap() -> impl IntoView {
                     <div class="flex flex-col gap-1 ml-6" class:hidden={move || !show_terrs.get()}>
                         <Checkbox id="resico" checked={show_res}>"Resource icons"</Checkbox>
                         <Checkbox id="timers" checked={show_timers}>"Timers"</Checkbox>
+                        <ColorModeSelect mode=color_mode />
                     </div>
                 </div>
                 <Checkbox id="conns" checked={show_conns}>"Connections"</Checkbox>
             </div>
 
+            <button class="p-2 m-2 border-neutral-600 border rounded-md hover:bg-neutral-700" on:click={
+                let owner = Owner::new();
+                move |_| {
+                    owner.with(move || {
+                        dialogs.add("terr_table", move || terr_table(terrs.into(), state.into(), selected));
+                    });
+                }
+            }>
+                "Territory table"
+            </button>
+
+            <HighlightPanel state guilds=highlighted_guilds />
+
+            <FrontlinePanel state frontline selected />
+
+            <AttackRoutePanel state planner selected />
+
+            <WatchPanel />
+
+            // captures
+            <CaptureLog captures terrs selected />
+
             // guild leaderboard
-            <Gleaderboard state show_guild_leaderboard/>
+            <Gleaderboard state show_guild_leaderboard focusable=true/>
         </Sidebar>
 
         // selected terr info
@@ -190,6 +298,8 @@ pub fn WarMap() -> impl IntoView {
                 <SideCard on_close=move |_| selected.set(None)>
                     <TerrStats name={sel.clone()} terrs state />
 
+                    <RouteInfo name={sel.clone()} routes show_routes />
+
                     <TerrCalc name={sel} terrs state />
                 </SideCard>
             })
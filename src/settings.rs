@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     sync::{Arc, LazyLock, Mutex},
 };
 
@@ -10,22 +10,58 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 struct Settings {
     toggles: HashMap<Arc<str>, bool>,
+    #[serde(default)]
+    lists: HashMap<Arc<str>, BTreeSet<Arc<str>>>,
 }
 
+/// A toggle which was set for the current session only
+#[derive(Debug, Clone, Copy)]
+struct UnsavedToggle {
+    /// The value set for the session
+    value: bool,
+    /// The value saved before, which stays saved while the toggle keeps the session value
+    saved: Option<bool>,
+}
+
+/// The settings, the toggles which aren't saved and the owner of the application root which outlives every component
+/// using them
 #[derive(Clone)]
-struct SettingsContext(RwSignal<Settings>);
+struct SettingsContext(
+    RwSignal<Settings>,
+    StoredValue<HashMap<Arc<str>, UnsavedToggle>>,
+    Owner,
+);
 
 /// Function for loading the settings from local storage and providing them to the context. This function should be called once at the start of the application.
 pub fn provide_settings() {
     let settings: Settings = gloo_storage::LocalStorage::get("settings").unwrap_or_default();
 
     let signal = RwSignal::new(settings);
+    let unsaved = StoredValue::new(HashMap::<Arc<str>, UnsavedToggle>::new());
 
     Effect::new(move || {
-        update_settings(signal.get());
+        let mut settings = signal.get();
+
+        unsaved.update_value(|unsaved| {
+            // toggles changed after they were set for the session are saved again
+            unsaved.retain(|name, t| settings.toggles.get(name) == Some(&t.value));
+
+            for (name, t) in unsaved.iter() {
+                match t.saved {
+                    Some(saved) => settings.toggles.insert(name.clone(), saved),
+                    None => settings.toggles.remove(name),
+                };
+            }
+        });
+
+        update_settings(settings);
     });
 
-    provide_context(SettingsContext(signal));
+    provide_context(SettingsContext(
+        signal,
+        unsaved,
+        Owner::current().expect("no reactive owner"),
+    ));
 }
 
 fn update_settings(settings: Settings) {
@@ -57,9 +93,8 @@ pub fn use_toggle(name: impl AsRef<str> + 'static, default: bool) -> RwSignal<bo
     }
 
     // get the settings context
-    let settings = use_context::<SettingsContext>()
-        .expect("attempted to use toggle outside of settings context")
-        .0;
+    let SettingsContext(settings, ..) = use_context::<SettingsContext>()
+        .expect("attempted to use toggle outside of settings context");
 
     // get the toggle value from the settings and fall back to the default if it doesn't exist
     let option = settings
@@ -89,3 +124,93 @@ pub fn use_toggle(name: impl AsRef<str> + 'static, default: bool) -> RwSignal<bo
 
     signal.into()
 }
+
+/// Set a toggle for the current session only, such as from a shared link, without saving the value
+///
+/// The previously saved value stays saved until the toggle is changed again.
+///
+/// # Arguments
+///
+/// * `name` - The name of the toggle setting.
+/// * `toggle` - The signal of the toggle setting returned by [`use_toggle`].
+/// * `value` - The value for the session.
+pub fn set_toggle_unsaved(name: &str, toggle: RwSignal<bool>, value: bool) {
+    if toggle.get_untracked() == value {
+        return;
+    }
+
+    let SettingsContext(settings, unsaved, _) = use_context::<SettingsContext>()
+        .expect("attempted to use toggle outside of settings context");
+
+    let name: Arc<str> = name.into();
+
+    unsaved.update_value(|unsaved| {
+        // keep the value saved before the first session value
+        let saved = unsaved.get(&name).map_or_else(
+            || settings.read_untracked().toggles.get(&name).copied(),
+            |t| t.saved,
+        );
+
+        unsaved.insert(name.clone(), UnsavedToggle { value, saved });
+    });
+
+    // write the setting right away so that it isn't saved before the toggle updates it
+    settings.update(|s| _ = s.toggles.insert(name, value));
+    toggle.set(value);
+}
+
+/// Signal of a list setting
+type ListSignal = ArcRwSignal<BTreeSet<Arc<str>>>;
+
+/// The static variable for storing the list signals which are currently in use.
+static LISTS: LazyLock<Mutex<HashMap<Arc<str>, ListSignal>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A function that retrieves a signal for a given list setting, such as a set of guild tags. If the signal or setting
+/// doesn't yet exist it will be created as an empty list.
+///
+/// # Arguments
+///
+/// * `name` - The name of the list setting.
+pub fn use_list(name: impl AsRef<str> + 'static) -> RwSignal<BTreeSet<Arc<str>>> {
+    let mut lists = LISTS.lock().unwrap();
+
+    // check if the signal already exists
+    if let Some(signal) = lists.get(name.as_ref()) {
+        let signal = signal.clone();
+        drop(lists);
+        return signal.into();
+    }
+
+    // get the settings context
+    let SettingsContext(settings, _, root) = use_context::<SettingsContext>()
+        .expect("attempted to use list outside of settings context");
+
+    let list = settings
+        .read_untracked()
+        .lists
+        .get(name.as_ref())
+        .cloned()
+        .unwrap_or_default();
+
+    let signal = ArcRwSignal::new(list);
+
+    lists.insert(name.as_ref().into(), signal.clone());
+    drop(lists);
+
+    // create an effect to update the settings when the signal changes
+    // the effect is owned by the root so that it keeps running after the component which created it is gone
+    root.with(|| {
+        Effect::new({
+            let signal = signal.clone();
+            move || {
+                settings
+                    .write()
+                    .lists
+                    .insert(name.as_ref().into(), signal.get());
+            }
+        });
+    });
+
+    signal.into()
+}
@@ -0,0 +1,220 @@
+//! Map state kept in the url query so that links open the same view
+
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+use web_sys::{js_sys::encode_uri_component, wasm_bindgen::JsValue};
+
+use crate::{settings::set_toggle_unsaved, wynnmap::context::MapPosition};
+
+const X_PARAM: &str = "x";
+const Z_PARAM: &str = "z";
+const ZOOM_PARAM: &str = "zoom";
+const TERR_PARAM: &str = "terr";
+const GUILDS_PARAM: &str = "guilds";
+const LAYERS_PARAM: &str = "layers";
+const HIDE_PARAM: &str = "hide";
+
+/// Delay before the url is updated so that moving the map doesn't replace the history entry every frame
+const UPDATE_DELAY: Duration = Duration::from_millis(300);
+
+/// Parts of the state of a mode which are kept in the url next to the map position
+#[derive(Clone, Default)]
+pub struct UrlState {
+    /// The selected territory
+    pub selected: Option<RwSignal<Option<Arc<str>>>>,
+    /// Tags of the highlighted guilds
+    pub guilds: Option<RwSignal<BTreeSet<Arc<str>>>>,
+    /// Layer toggles of the mode by the name of their setting
+    pub layers: Vec<(&'static str, RwSignal<bool>)>,
+    /// Toggles which are only known once the data of the mode has loaded
+    pub toggles: Option<UrlToggles>,
+}
+
+/// Toggles created from loaded data, such as the gather material toggles
+///
+/// They are kept in the url as the list of hidden toggles since most of them are usually shown.
+#[derive(Clone, Copy)]
+pub struct UrlToggles {
+    /// Prefix of the setting names of the toggles which is left out of the url
+    pub prefix: &'static str,
+    /// The toggles by their setting name without the prefix
+    pub toggles: Signal<Vec<(Arc<str>, RwSignal<bool>)>>,
+}
+
+/// Restore the map state from the url query and keep the query updated as the state changes
+///
+/// Query parameters not used by the map state, such as the session of the planning mode, are kept. Layers restored from
+/// the url are only set for the session so that opening a link doesn't change the saved settings.
+pub fn use_url_state(state: UrlState) {
+    let camera = expect_context::<MapPosition>();
+    let UrlState {
+        selected,
+        guilds,
+        layers,
+        toggles,
+    } = state;
+
+    let query = use_query_map();
+    let hidden = query.with_untracked(|q| q.get(HIDE_PARAM));
+
+    query.with_untracked(|query| {
+        let num = |key| query.get(key).and_then(|v| v.parse::<f64>().ok());
+
+        if let (Some(x), Some(z)) = (num(X_PARAM), num(Z_PARAM)) {
+            let zoom = num(ZOOM_PARAM).unwrap_or_else(|| camera.zoom.get_untracked());
+            camera.jump_to([x, z], zoom);
+        }
+
+        if let (Some(selected), Some(terr)) = (selected, query.get(TERR_PARAM)) {
+            selected.set(Some(Arc::from(terr)));
+        }
+
+        if let (Some(guilds), Some(tags)) = (guilds, query.get(GUILDS_PARAM)) {
+            guilds.set(split_list(&tags).map(Arc::from).collect());
+        }
+
+        if let Some(enabled) = query.get(LAYERS_PARAM) {
+            let enabled: BTreeSet<_> = split_list(&enabled).collect();
+
+            for (name, toggle) in &layers {
+                set_toggle_unsaved(name, *toggle, enabled.contains(name));
+            }
+        }
+    });
+
+    // the toggles are restored once they have been created from the loaded data
+    if let (Some(UrlToggles { prefix, toggles }), Some(hidden)) = (toggles, hidden.clone()) {
+        let restored = StoredValue::new(BTreeSet::new());
+
+        Effect::new(move || {
+            let hidden: BTreeSet<_> = split_list(&hidden).collect();
+
+            for (name, toggle) in toggles.get() {
+                if restored.try_update_value(|r| r.insert(name.clone())) == Some(true) {
+                    let setting = format!("{prefix}{name}");
+                    set_toggle_unsaved(&setting, toggle, !hidden.contains(&*name));
+                }
+            }
+        });
+    }
+
+    let pending = StoredValue::new(None::<TimeoutHandle>);
+
+    Effect::new(move || {
+        camera.position.track();
+        camera.zoom.track();
+
+        let center = camera.center();
+        let zoom = camera.zoom.get();
+
+        let params = [
+            (X_PARAM, Some(format!("{:.0}", center[0]))),
+            (Z_PARAM, Some(format!("{:.0}", center[1]))),
+            (ZOOM_PARAM, Some(format!("{zoom:.3}"))),
+            (
+                TERR_PARAM,
+                selected.and_then(|s| s.get()).map(|s| s.to_string()),
+            ),
+            (
+                GUILDS_PARAM,
+                guilds
+                    .map(|g| {
+                        g.read()
+                            .iter()
+                            .map(AsRef::as_ref)
+                            .collect::<Vec<&str>>()
+                            .join(",")
+                    })
+                    .filter(|g| !g.is_empty()),
+            ),
+            (
+                LAYERS_PARAM,
+                (!layers.is_empty()).then(|| {
+                    layers
+                        .iter()
+                        .filter(|(_, t)| t.get())
+                        .map(|(n, _)| *n)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+            ),
+            (
+                HIDE_PARAM,
+                toggles.and_then(|UrlToggles { toggles, .. }| {
+                    let toggles = toggles.get();
+
+                    // keep the hidden toggles of the url until the toggles have loaded
+                    if toggles.is_empty() {
+                        return hidden.clone();
+                    }
+
+                    Some(
+                        toggles
+                            .iter()
+                            .filter(|(_, t)| !t.get())
+                            .map(|(n, _)| n.as_ref())
+                            .collect::<Vec<_>>()
+                            .join(","),
+                    )
+                }),
+            ),
+        ];
+
+        if let Some(handle) = pending.get_value() {
+            handle.clear();
+        }
+
+        pending
+            .set_value(set_timeout_with_handle(move || replace_query(&params), UPDATE_DELAY).ok());
+    });
+
+    // a pending update would otherwise write this mode's state into the url of the next mode
+    on_cleanup(move || {
+        if let Some(handle) = pending.try_get_value().flatten() {
+            handle.clear();
+        }
+    });
+}
+
+fn split_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Replace the given query parameters of the current url without adding a history entry
+fn replace_query(params: &[(&str, Option<String>)]) {
+    let location = window().location();
+    let search = location.search().unwrap_or_default();
+
+    // keep the parameters of others as they are
+    let mut query: Vec<String> = search
+        .trim_start_matches('?')
+        .split('&')
+        .filter(|p| {
+            let key = p.split('=').next().unwrap_or_default();
+            !key.is_empty() && !params.iter().any(|(k, _)| *k == key)
+        })
+        .map(String::from)
+        .collect();
+
+    for (key, value) in params {
+        if let Some(value) = value {
+            query.push(format!(
+                "{key}={}",
+                String::from(encode_uri_component(value))
+            ));
+        }
+    }
+
+    let url = format!(
+        "{}?{}{}",
+        location.pathname().unwrap_or_default(),
+        query.join("&"),
+        location.hash().unwrap_or_default()
+    );
+
+    let _ = window()
+        .history()
+        .and_then(|h| h.replace_state_with_url(&JsValue::NULL, "", Some(&url)));
+}
@@ -35,3 +35,15 @@ pub fn fmt_time_short(time: SignedDuration) -> String {
 pub fn as_px(px: impl Display) -> String {
     format!("{px}px")
 }
+
+/// Format a string in Titlecase
+pub fn titlecase(name: impl AsRef<str>) -> String {
+    let mut c = name.as_ref().chars();
+    match c.next() {
+        None => String::new(),
+        Some(f) => f
+            .to_uppercase()
+            .chain(c.flat_map(char::to_lowercase))
+            .collect(),
+    }
+}
@@ -0,0 +1,192 @@
+use std::{cell::Cell, rc::Rc};
+
+use leptos::prelude::*;
+use web_sys::js_sys::Date;
+use wynnmap_types::Region;
+
+use crate::wynnmap::{
+    ZOOM_MAX, ZOOM_MIN,
+    context::MapPosition,
+    util::{get_viewport_middle, zip_map},
+};
+
+/// The point on the map atlas shown when the map is opened
+pub const HOME: [f64; 2] = [-200.0, -2400.0];
+
+/// Duration of flying to a point in milliseconds
+const FLY_DURATION: f64 = 600.0;
+/// Space left around regions when fitting them on the screen in pixels
+const FIT_PADDING: f64 = 64.0;
+/// Maximum zoom used when fitting regions so that small regions aren't zoomed into too far
+const FIT_MAX_ZOOM: f64 = 4.0;
+
+/// Fraction of the velocity kept after every millisecond of gliding
+const GLIDE_FRICTION: f64 = 0.995;
+/// Speed in pixels per millisecond below which gliding stops
+const GLIDE_MIN_SPEED: f64 = 0.02;
+/// Time in milliseconds after the last move of a drag after which releasing it doesn't glide
+const GLIDE_MAX_DELAY: f64 = 50.0;
+
+impl MapPosition {
+    /// The point on the map atlas in the middle of the screen
+    pub fn center(self) -> [f64; 2] {
+        let zoom = self.zoom.get_untracked();
+
+        zip_map(
+            get_viewport_middle(),
+            self.position.get_untracked(),
+            |m, p| (m - p) / zoom,
+        )
+    }
+
+    /// Instantly move the map to show a point on the map atlas in the middle of the screen at the given zoom
+    pub fn jump_to(self, point: [f64; 2], zoom: f64) {
+        let zoom = zoom.clamp(ZOOM_MIN, ZOOM_MAX);
+
+        self.zoom.set(zoom);
+        self.position
+            .set(zip_map(get_viewport_middle(), point, |m, p| {
+                p.mul_add(-zoom, m)
+            }));
+    }
+
+    /// Smoothly move the map to show a point on the map atlas in the middle of the screen at the given zoom
+    pub fn fly_to(self, point: [f64; 2], zoom: f64) {
+        let start = self.center();
+        let start_zoom = self.zoom.get_untracked();
+        let end_zoom = zoom.clamp(ZOOM_MIN, ZOOM_MAX);
+
+        self.animate(move |time| {
+            let progress = (time / FLY_DURATION).min(1.0);
+            let t = ease_in_out(progress);
+
+            // zoom geometrically so that zooming in and out feel equally fast
+            let zoom = start_zoom * (end_zoom / start_zoom).powf(t);
+            let center = zip_map(start, point, |s, e| (e - s).mul_add(t, s));
+
+            self.jump_to(center, zoom);
+
+            progress < 1.0
+        });
+    }
+
+    /// Fly to a region fitting it on the screen
+    pub fn fit_region(self, region: Region) {
+        self.fit_regions([region]);
+    }
+
+    /// Fly to a group of regions fitting all of them on the screen
+    pub fn fit_regions(self, regions: impl IntoIterator<Item = Region>) {
+        let Some([left, top, right, bottom]) = regions.into_iter().fold(None, |acc, r| {
+            let [l, t, r, b] = [r.left_side(), r.top_side(), r.right_side(), r.bottom_side()];
+
+            Some(acc.map_or([l, t, r, b], |[al, at, ar, ab]: [i32; 4]| {
+                [al.min(l), at.min(t), ar.max(r), ab.max(b)]
+            }))
+        }) else {
+            return;
+        };
+
+        let [left, top, right, bottom] = [left, top, right, bottom].map(f64::from);
+        let screen = get_viewport_middle().map(|m| m.mul_add(2.0, -2.0 * FIT_PADDING).max(1.0));
+
+        let zoom = (screen[0] / (right - left).max(1.0))
+            .min(screen[1] / (bottom - top).max(1.0))
+            .min(FIT_MAX_ZOOM);
+
+        self.fly_to([(left + right) / 2.0, (top + bottom) / 2.0], zoom);
+    }
+
+    /// Keep moving the map after a drag was released, slowing down over time
+    ///
+    /// The velocity is in pixels per millisecond.
+    pub fn glide(self, velocity: [f64; 2]) {
+        if velocity[0].hypot(velocity[1]) < GLIDE_MIN_SPEED {
+            return;
+        }
+
+        let velocity = Cell::new(velocity);
+        let last = Cell::new(0.0);
+
+        self.animate(move |time| {
+            let dt = time - last.replace(time);
+            let v = velocity.get().map(|v| v * GLIDE_FRICTION.powf(dt));
+
+            self.position
+                .update(|p| *p = zip_map(*p, v, |p, v| v.mul_add(dt, p)));
+            velocity.set(v);
+
+            v[0].hypot(v[1]) >= GLIDE_MIN_SPEED
+        });
+    }
+
+    /// Stop the running camera animation
+    pub fn stop(self) {
+        self.animation.update_value(|a| *a = a.wrapping_add(1));
+    }
+
+    /// Run a step function every frame with the time since the start until it returns false or another animation is
+    /// started
+    fn animate(self, step: impl Fn(f64) -> bool + 'static) {
+        self.stop();
+
+        let id = self.animation.get_value();
+        let start = Date::now();
+
+        frame(self, id, start, Rc::new(step));
+    }
+}
+
+fn frame(camera: MapPosition, id: u64, start: f64, step: Rc<dyn Fn(f64) -> bool>) {
+    request_animation_frame(move || {
+        if camera.animation.try_get_value() != Some(id) {
+            return;
+        }
+
+        if step(Date::now() - start) {
+            frame(camera, id, start, step);
+        }
+    });
+}
+
+/// Cubic ease in and out
+fn ease_in_out(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t.powi(3)
+    } else {
+        1.0 - (-2.0f64).mul_add(t, 2.0).powi(3) / 2.0
+    }
+}
+
+/// Tracks the velocity of a drag so that the map can glide after it is released
+#[derive(Clone, Copy, Default)]
+pub struct Momentum {
+    velocity: [f64; 2],
+    last: f64,
+}
+
+impl Momentum {
+    /// Record a movement of the drag in pixels
+    pub fn track(&mut self, delta: [f64; 2]) {
+        let now = Date::now();
+        let dt = (now - self.last).max(1.0);
+        let velocity = delta.map(|d| d / dt);
+
+        // smooth out the noise of individual events unless the drag had stopped
+        self.velocity = if dt > GLIDE_MAX_DELAY {
+            velocity
+        } else {
+            zip_map(self.velocity, velocity, |o, n| o.mul_add(0.3, n * 0.7))
+        };
+        self.last = now;
+    }
+
+    /// Velocity of the drag when it is released, zero if the drag had stopped before
+    pub fn release(self) -> [f64; 2] {
+        if Date::now() - self.last > GLIDE_MAX_DELAY {
+            [0.0; 2]
+        } else {
+            self.velocity
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use jiff::{SignedDuration, Timestamp};
+use leptos::prelude::*;
+use wynnmap_types::{
+    terr::{TerrState, Territory},
+    tier::WynnTier,
+};
+
+/// Time held at which the time held gradient reaches its end
+const MAX_TIME_HELD: SignedDuration = SignedDuration::from_hours(24 * 12);
+
+/// Color of territories which have no value for the current color mode
+const NO_VALUE: (u8, u8, u8) = (115, 115, 115);
+
+/// Gradient from good to bad used by the continuous color modes
+const GRADIENT: [(f64, (u8, u8, u8)); 3] = [
+    (0.0, (0x55, 0xFF, 0x55)),
+    (0.5, (0xFF, 0xFF, 0x55)),
+    (1.0, (0xAA, 0x00, 0x00)),
+];
+
+/// Colors of the produced resources
+const RESOURCE_COLORS: [(&str, (u8, u8, u8)); 5] = [
+    ("Emeralds", (0x10, 0xB9, 0x81)),
+    ("Ore", (0xA8, 0xA2, 0x9E)),
+    ("Crops", (0xFA, 0xCC, 0x15)),
+    ("Fish", (0x38, 0xBD, 0xF8)),
+    ("Wood", (0xA1, 0x62, 0x07)),
+];
+
+const TIERS: [WynnTier; 5] = [
+    WynnTier::VeryLow,
+    WynnTier::Low,
+    WynnTier::Medium,
+    WynnTier::High,
+    WynnTier::VeryHigh,
+];
+
+/// What the territories are colored by
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    #[default]
+    Guild,
+    Treasury,
+    Defences,
+    TimeHeld,
+    Resource,
+    Storage,
+}
+
+impl ColorMode {
+    pub const ALL: [Self; 6] = [
+        Self::Guild,
+        Self::Treasury,
+        Self::Defences,
+        Self::TimeHeld,
+        Self::Resource,
+        Self::Storage,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Guild => "Guild",
+            Self::Treasury => "Treasury",
+            Self::Defences => "Defences",
+            Self::TimeHeld => "Time held",
+            Self::Resource => "Produced resource",
+            Self::Storage => "Storage fill",
+        }
+    }
+
+    /// Whether the mode depends on the current time
+    pub const fn uses_time(self) -> bool {
+        matches!(self, Self::TimeHeld)
+    }
+
+    /// Color of a territory in this mode
+    pub fn color(self, terr: &Territory, state: &TerrState, now: Timestamp) -> (u8, u8, u8) {
+        match self {
+            Self::Guild => state.guild.get_color(),
+            Self::Treasury => hex_rgb(state.treasury.color()),
+            Self::Defences => hex_rgb(state.defences.color()),
+            Self::TimeHeld => state.acquired.map_or(NO_VALUE, |acquired| {
+                // a log scale keeps the first hours apart while still reaching the end eventually
+                let hours = now.duration_since(acquired).as_hours().max(0) as f64;
+                let max = MAX_TIME_HELD.as_hours() as f64;
+
+                gradient(hours.ln_1p() / max.ln_1p())
+            }),
+            Self::Resource => primary_resource(terr).map_or(NO_VALUE, |i| RESOURCE_COLORS[i].1),
+            Self::Storage => {
+                let r = &state.resources;
+                let values = [&r.emerald, &r.ore, &r.crop, &r.fish, &r.wood];
+
+                let stored: i32 = values.iter().map(|v| v.stored).sum();
+                let limit: i32 = values.iter().map(|v| v.limit).sum();
+
+                if limit > 0 {
+                    gradient(f64::from(stored) / f64::from(limit))
+                } else {
+                    NO_VALUE
+                }
+            }
+        }
+    }
+
+    /// Legend explaining the colors of this mode
+    pub fn legend(self) -> Legend {
+        let tiers = || {
+            TIERS
+                .into_iter()
+                .map(|t| (t.to_string(), t.color().to_string()))
+                .collect()
+        };
+
+        match self {
+            Self::Guild => Legend::None,
+            Self::Treasury | Self::Defences => Legend::Swatches(tiers()),
+            Self::TimeHeld => Legend::Gradient("0h", "12d+"),
+            Self::Resource => Legend::Swatches(
+                RESOURCE_COLORS
+                    .iter()
+                    .map(|(n, c)| (n.to_string(), rgb_css(*c)))
+                    .collect(),
+            ),
+            Self::Storage => Legend::Gradient("Empty", "Full"),
+        }
+    }
+}
+
+/// Legend of a color mode
+pub enum Legend {
+    None,
+    /// Labeled css colors
+    Swatches(Vec<(String, String)>),
+    /// The good to bad gradient with labels for both ends
+    Gradient(&'static str, &'static str),
+}
+
+/// Index of the main resource produced by a territory in [`RESOURCE_COLORS`]
+fn primary_resource(terr: &Territory) -> Option<usize> {
+    let g = terr.generates;
+
+    if g.has_emerald() {
+        return Some(0);
+    }
+
+    [g.ore, g.crop, g.fish, g.wood]
+        .into_iter()
+        .enumerate()
+        .filter(|(_, v)| *v > 0)
+        .max_by_key(|(_, v)| *v)
+        .map(|(i, _)| i + 1)
+}
+
+fn gradient(t: f64) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+
+    let (start, end) = GRADIENT
+        .windows(2)
+        .map(|w| (w[0], w[1]))
+        .find(|(_, e)| t <= e.0)
+        .unwrap_or((GRADIENT[1], GRADIENT[2]));
+
+    let f = (t - start.0) / (end.0 - start.0);
+    let lerp = |a: u8, b: u8| {
+        (f64::from(b) - f64::from(a))
+            .mul_add(f, f64::from(a))
+            .round() as u8
+    };
+
+    (
+        lerp(start.1.0, end.1.0),
+        lerp(start.1.1, end.1.1),
+        lerp(start.1.2, end.1.2),
+    )
+}
+
+fn hex_rgb(hex: &str) -> (u8, u8, u8) {
+    let hex = hex.trim_start_matches('#');
+    let parse = |i: usize| {
+        hex.get(i..i + 2)
+            .and_then(|s| u8::from_str_radix(s, 16).ok())
+            .unwrap_or(0)
+    };
+
+    (parse(0), parse(2), parse(4))
+}
+
+fn rgb_css((r, g, b): (u8, u8, u8)) -> String {
+    format!("rgb({r} {g} {b})")
+}
+
+/// Select for changing the color mode
+#[component]
+pub fn ColorModeSelect(mode: RwSignal<ColorMode>) -> impl IntoView {
+    view! {
+        <label class="flex flex-col">
+            "Color territories by:"
+            <select
+                class="p-1 rounded border-1 border-neutral-600 bg-neutral-900"
+                on:change:target=move |ev| {
+                    let value = ev.target().value();
+
+                    if let Some(m) = ColorMode::ALL.into_iter().find(|m| m.name() == value) {
+                        mode.set(m);
+                    }
+                }
+            >
+                {ColorMode::ALL.map(|m| view! {
+                    <option value={m.name()} selected={move || mode.get() == m}>{m.name()}</option>
+                })}
+            </select>
+        </label>
+    }
+}
+
+/// Overlay explaining the colors of the current color mode
+#[component]
+pub fn ColorLegend(#[prop(into)] mode: Signal<ColorMode>) -> impl IntoView {
+    move || {
+        let content = match mode.get().legend() {
+            Legend::None => return None,
+            Legend::Swatches(swatches) => swatches
+                .into_iter()
+                .map(|(label, color)| {
+                    view! {
+                        <div class="flex items-center gap-2">
+                            <span class="inline-block size-3 rounded-sm" style:background-color=color></span>
+                            {label}
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()
+                .into_any(),
+            Legend::Gradient(from, to) => {
+                let stops = GRADIENT
+                    .iter()
+                    .map(|(t, c)| format!("{} {}%", rgb_css(*c), t * 100.0))
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                view! {
+                    <div class="h-3 w-48 rounded-sm" style:background=format!("linear-gradient(to right, {stops})")></div>
+                    <div class="flex justify-between text-sm">
+                        <span>{from}</span>
+                        <span>{to}</span>
+                    </div>
+                }
+                .into_any()
+            }
+        };
+
+        Some(view! {
+            <div class="fixed bottom-4 left-1/2 -translate-x-1/2 bg-neutral-900 text-white rounded-md p-2 flex flex-col gap-1">
+                <h2 class="text-lg">{mode.get().name()}</h2>
+                {content}
+            </div>
+        })
+    }
+}
@@ -5,13 +5,69 @@ use std::{
 };
 
 use leptos::prelude::*;
-use wynnmap_types::terr::Territory;
+use wynnmap_types::terr::{TerrState, Territory};
 
 use crate::util::as_px;
 
+/// Map layer drawing the connections between territories
+///
+/// When a state and focused guilds are given the connections between territories of the focused guilds are emphasised.
+/// When a route is given it is drawn over the connections with its hops numbered.
 #[component]
-pub fn Connections(#[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>) -> impl IntoView {
+pub fn Connections(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into, optional)] state: Option<Signal<BTreeMap<Arc<str>, TerrState>>>,
+    /// Tags of the focused guilds
+    #[prop(into, optional)]
+    focus: Signal<BTreeSet<Arc<str>>>,
+    /// Names of the territories along a route
+    #[prop(into, optional)]
+    route: Option<Signal<Vec<Arc<str>>>>,
+) -> impl IntoView {
     let conn_path = move || create_route_paths(&terrs.read());
+    let focus_path = move || {
+        let focus = focus.read();
+        let state = state?;
+
+        if focus.is_empty() {
+            return None;
+        }
+
+        let state = state.read();
+        let focused: BTreeMap<_, _> = terrs
+            .read()
+            .iter()
+            .filter(|(n, _)| {
+                state
+                    .get(*n)
+                    .is_some_and(|s| focus.contains(&s.guild.prefix))
+            })
+            .map(|(n, t)| (n.clone(), t.clone()))
+            .collect();
+
+        Some(create_route_paths(&focused))
+    };
+    // midpoints of the territories on the route
+    let route_points = move || {
+        let terrs = terrs.read();
+
+        route.map_or_else(Vec::new, |r| {
+            r.read()
+                .iter()
+                .filter_map(|n| Some(terrs.get(n)?.location.get_midpoint()))
+                .collect::<Vec<_>>()
+        })
+    };
+    let route_path = move || {
+        let mut path = String::new();
+
+        for (i, [x, y]) in route_points().into_iter().enumerate() {
+            let cmd = if i == 0 { 'M' } else { 'L' };
+            write!(path, "{cmd}{x} {y}").expect("Write should not fail");
+        }
+
+        path
+    };
     let bounds = Memo::new(move |_| bounds(&terrs.read()));
 
     let viewbox = move || {
@@ -52,6 +108,21 @@ pub fn Connections(#[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>) -
                 />
                 "
             }/>
+
+            {move || focus_path().map(|path| view! {
+                <path d={path.clone()} style="fill:none;stroke:black;stroke-width:10;" stroke-linecap="round" />
+                <path d={path} style="fill:none;stroke:#facc15;stroke-width:5;" stroke-linecap="round" />
+            })}
+
+            // route with its hops numbered, the start is the territory the route is taken from
+            <path d={route_path} style="fill:none;stroke:black;stroke-width:14;" stroke-linecap="round" stroke-linejoin="round" />
+            <path d={route_path} style="fill:none;stroke:#ef4444;stroke-width:8;" stroke-linecap="round" stroke-linejoin="round" />
+            {move || route_points().into_iter().enumerate().skip(1).map(|(i, [x, y])| view! {
+                <circle cx=x cy=y r="24" style="fill:#ef4444;stroke:black;stroke-width:4;" />
+                <text x=x y=y text-anchor="middle" dominant-baseline="central" style="fill:white;font-size:28px;font-weight:bold;">
+                    {i}
+                </text>
+            }).collect::<Vec<_>>()}
         </svg>
     }
 }
@@ -92,7 +163,7 @@ fn create_route_paths(terrs: &BTreeMap<Arc<str>, Territory>) -> String {
     pathing
 }
 
-fn bounds(terrs: &BTreeMap<Arc<str>, Territory>) -> (i32, i32, i32, i32) {
+pub(super) fn bounds(terrs: &BTreeMap<Arc<str>, Territory>) -> (i32, i32, i32, i32) {
     let mut max_x = 0;
     let mut min_x = 0;
     let mut max_y = 0;
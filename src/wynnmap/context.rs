@@ -6,7 +6,10 @@ use wynnmap_types::maptile::MapTile;
 use crate::{
     datasource,
     dialog::{Dialogs, info::info_dialog},
-    wynnmap::util::get_viewport_middle,
+    wynnmap::{
+        camera::HOME,
+        util::{get_viewport_middle, zip_map},
+    },
 };
 
 #[component]
@@ -18,20 +21,27 @@ pub fn MapContextProvider(children: Children) -> impl IntoView {
     children()
 }
 
-#[derive(Clone)]
+/// Position of the map on the screen
+///
+/// The methods for moving the map smoothly are in the camera module.
+#[derive(Clone, Copy)]
 pub struct MapPosition {
     pub position: RwSignal<[f64; 2]>,
     pub zoom: RwSignal<f64>,
+    /// Id of the running camera animation, changing it stops the animation
+    pub(super) animation: StoredValue<u64>,
 }
 
 fn provide_map_position() {
-    let screen_middle = get_viewport_middle();
-    // use the midpoint to position the map so that it is centered
-    let position = RwSignal::new([100.0 + screen_middle[0], 1200.0 + screen_middle[1]]);
-
-    let zoom = RwSignal::new(0.5);
-
-    provide_context(MapPosition { position, zoom });
+    let zoom = 0.5;
+    // use the midpoint to position the map so that the home point is centered
+    let position = zip_map(get_viewport_middle(), HOME, |m, h| h.mul_add(-zoom, m));
+
+    provide_context(MapPosition {
+        position: RwSignal::new(position),
+        zoom: RwSignal::new(zoom),
+        animation: StoredValue::new(0),
+    });
 }
 
 /// Mouse position on the map atlas
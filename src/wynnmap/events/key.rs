@@ -1,41 +1,25 @@
 use leptos::{ev, prelude::*};
 use web_sys::KeyboardEvent;
 
-use crate::wynnmap::util::{apply_zoom, apply_zoom_compensation, get_viewport_middle};
+use crate::wynnmap::{
+    camera::HOME,
+    context::MapPosition,
+    util::{apply_zoom, get_viewport_middle},
+};
+
+pub fn handlers(camera: MapPosition, transitioning: RwSignal<bool>) {
+    let MapPosition { position, zoom, .. } = camera;
 
-pub fn handlers(position: RwSignal<[f64; 2]>, zoom: RwSignal<f64>, transitioning: RwSignal<bool>) {
     let onkeydown = move |e: KeyboardEvent| {
         match e.key().as_str() {
             // 0 key - reset zoom
-            "0" => {
-                let old_zoom = zoom.get();
-                let new_zoom = 0.5;
-                // reset the zoom
-                zoom.set(new_zoom);
-
-                // perform zoom compensation
-                // get middle point of the screen
-                let center = get_viewport_middle();
-                // calculate the zoom compensation
-                apply_zoom_compensation(center, old_zoom, new_zoom, position);
-
-                // do transition
-                transitioning.set(true);
-            }
+            "0" => camera.fly_to(camera.center(), 0.5),
             // Home - reset position
-            "Home" => {
-                let screen_middle = get_viewport_middle();
-                let zoom = zoom.get() * 2.0;
-
-                position.set([
-                    100.0f64.mul_add(zoom, screen_middle[0]),
-                    1200.0f64.mul_add(zoom, screen_middle[1]),
-                ]);
-
-                transitioning.set(true);
-            }
+            "Home" => camera.fly_to(HOME, zoom.get()),
             // plus key - zoom in
             "+" => {
+                camera.stop();
+
                 // get middle point of the screen
                 let center = get_viewport_middle();
                 // apply the zoom
@@ -46,6 +30,8 @@ pub fn handlers(position: RwSignal<[f64; 2]>, zoom: RwSignal<f64>, transitioning
             }
             // minus key - zoom out
             "-" => {
+                camera.stop();
+
                 // get middle point of the screen
                 let center = get_viewport_middle();
                 // apply the zoom
@@ -56,24 +42,28 @@ pub fn handlers(position: RwSignal<[f64; 2]>, zoom: RwSignal<f64>, transitioning
             }
             // ArrowUp - move up
             "ArrowUp" => {
+                camera.stop();
                 position.update(|[_, y]| *y += 100.0 / zoom.get());
 
                 transitioning.set(true);
             }
             // ArrowDown - move down
             "ArrowDown" => {
+                camera.stop();
                 position.update(|[_, y]| *y -= 100.0 / zoom.get());
 
                 transitioning.set(true);
             }
             // ArrowLeft - move left
             "ArrowLeft" => {
+                camera.stop();
                 position.update(|[x, _]| *x += 100.0 / zoom.get());
 
                 transitioning.set(true);
             }
             // ArrowRight - move right
             "ArrowRight" => {
+                camera.stop();
                 position.update(|[x, _]| *x -= 100.0 / zoom.get());
 
                 transitioning.set(true);
@@ -1,7 +1,7 @@
 use leptos::prelude::*;
 use web_sys::{MouseEvent, WheelEvent};
 
-use crate::wynnmap::util::apply_zoom;
+use crate::wynnmap::{camera::Momentum, context::MapPosition, util::apply_zoom};
 
 pub struct MouseEventHandlers<MM, SM, EM, WH>
 where
@@ -17,9 +17,9 @@ where
 }
 
 pub fn handlers(
-    position: RwSignal<[f64; 2]>,
-    zoom: RwSignal<f64>,
+    camera: MapPosition,
     moving: RwSignal<bool>,
+    tool_drag: RwSignal<bool>,
     transitioning: RwSignal<bool>,
 ) -> MouseEventHandlers<
     impl Fn(MouseEvent) + Copy + 'static,
@@ -27,18 +27,25 @@ pub fn handlers(
     impl Fn(MouseEvent) + Copy + 'static,
     impl Fn(WheelEvent) + Copy + 'static,
 > {
+    let MapPosition { position, zoom, .. } = camera;
+
     // mouse position stored for zoom compensation
     let mousepos = RwSignal::new([0, 0]);
+    // speed of the drag for gliding after it is released
+    let momentum = StoredValue::new(Momentum::default());
 
     let mousemove = move |e: MouseEvent| {
         e.prevent_default();
 
         // if we are dragging move the map
         if moving.get() {
+            let delta = [e.movement_x(), e.movement_y()].map(f64::from);
+
             position.update(|[x, y]| {
-                *x += f64::from(e.movement_x());
-                *y += f64::from(e.movement_y());
+                *x += delta[0];
+                *y += delta[1];
             });
+            momentum.update_value(|m| m.track(delta));
         }
 
         mousepos.set([e.client_x(), e.client_y()]);
@@ -48,13 +55,22 @@ pub fn handlers(
     let start_mousemove = move |e: MouseEvent| {
         e.prevent_default();
 
-        moving.set(true);
+        // the map isn't moved while a tool is dragging
+        if !tool_drag.get() {
+            camera.stop();
+            momentum.set_value(Momentum::default());
+            moving.set(true);
+        }
     };
 
     // detect when a mouse drag ends
     let end_mousemove = move |e: MouseEvent| {
         e.prevent_default();
 
+        if moving.get() {
+            camera.glide(momentum.get_value().release());
+        }
+
         moving.set(false);
     };
 
@@ -63,6 +79,7 @@ pub fn handlers(
         e.prevent_default();
 
         // enable the transition when zooming with the mousewheel
+        camera.stop();
         transitioning.set(true);
 
         // get the mouse position
@@ -3,6 +3,17 @@ use web_sys::PointerEvent;
 
 use crate::wynnmap::{context::RelMousePos, util::zip_map};
 
+/// Drag events emitted by the map for tools which use the pointer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapDrag {
+    /// The pointer was pressed down at the given map position
+    Start([i32; 2]),
+    /// The pointer was moved to the given map position while pressed down
+    Move([i32; 2]),
+    /// The pointer was released or left the map
+    End,
+}
+
 pub struct PointerEventHandlers<PM, PD, PU, PL>
 where
     PM: Fn(PointerEvent) + Copy + 'static,
@@ -20,7 +31,9 @@ pub fn handlers(
     position: RwSignal<[f64; 2]>,
     zoom: RwSignal<f64>,
     moving: RwSignal<bool>,
+    tool_drag: RwSignal<bool>,
     onclick: Option<Callback<[i32; 2]>>,
+    ondrag: Option<Callback<MapDrag, bool>>,
 ) -> PointerEventHandlers<
     impl Fn(PointerEvent) + Copy + 'static,
     impl Fn(PointerEvent) + Copy + 'static,
@@ -39,19 +52,48 @@ pub fn handlers(
             let map_pos = position.get();
             let rel = zip_map(pos, map_pos, |p, m| ((p - m) / zoom) as i32);
             relmousepos.set(Some(rel));
+
+            if tool_drag.get()
+                && let Some(cb) = ondrag
+            {
+                cb.run(MapDrag::Move(rel));
+            }
         }
     };
 
     let dragstartpos = RwSignal::new([0; 2]);
 
+    // end a drag handled by a tool
+    let end_tool_drag = move || {
+        if tool_drag.get()
+            && let Some(cb) = ondrag
+        {
+            tool_drag.set(false);
+            cb.run(MapDrag::End);
+        }
+    };
+
     let pointerdown = move |e: PointerEvent| {
         dragstartpos.set([e.client_x(), e.client_y()]);
 
         // also update position on down to support touch events
         pointermove(e);
+
+        // let the tool take over the drag instead of moving the map
+        if let Some(cb) = ondrag
+            && let Some(pos) = relmousepos.get()
+        {
+            tool_drag.set(cb.run(MapDrag::Start(pos)));
+        }
     };
 
     let pointerup = move |e: PointerEvent| {
+        // drags handled by a tool don't emit clicks
+        if tool_drag.get() {
+            end_tool_drag();
+            return;
+        }
+
         let pos = [e.client_x(), e.client_y()];
         let startpos = dragstartpos.get();
 
@@ -67,6 +109,7 @@ pub fn handlers(
     };
 
     let pointerleave = move |_| {
+        end_tool_drag();
         relmousepos.set(None);
     };
 
@@ -1,25 +1,38 @@
 use leptos::prelude::*;
 use web_sys::{Touch, TouchEvent, TouchList};
 
-use crate::wynnmap::util::{apply_zoom, zip_map};
+use crate::wynnmap::{
+    camera::Momentum,
+    context::MapPosition,
+    util::{apply_zoom, zip_map},
+};
 
-pub struct TouchEventHandlers<TS, TM>
+pub struct TouchEventHandlers<TS, TM, TE>
 where
     TS: Fn(TouchEvent) + Copy + 'static,
     TM: Fn(TouchEvent) + Copy + 'static,
+    TE: Fn(TouchEvent) + Copy + 'static,
 {
     pub touchstart: TS,
     pub touchmove: TM,
+    pub touchend: TE,
 }
 
 pub fn handlers(
-    position: RwSignal<[f64; 2]>,
-    zoom: RwSignal<f64>,
+    camera: MapPosition,
     moving: RwSignal<bool>,
-) -> TouchEventHandlers<impl Fn(TouchEvent) + Copy + 'static, impl Fn(TouchEvent) + Copy + 'static>
-{
+    tool_drag: RwSignal<bool>,
+) -> TouchEventHandlers<
+    impl Fn(TouchEvent) + Copy + 'static,
+    impl Fn(TouchEvent) + Copy + 'static,
+    impl Fn(TouchEvent) + Copy + 'static,
+> {
+    let MapPosition { position, zoom, .. } = camera;
+
     // touch positions stored for touch events
     let tpos = RwSignal::new(Vec::new());
+    // speed of a single finger drag for gliding after it is released
+    let momentum = StoredValue::new(Momentum::default());
 
     // detect when a touch starts and update the active touches
     let touchstart = move |e: TouchEvent| {
@@ -27,7 +40,10 @@ pub fn handlers(
 
         tpos.set(get_touch_positions(&e.touches()));
 
-        if tpos.read().is_empty() {
+        camera.stop();
+        momentum.set_value(Momentum::default());
+
+        if tpos.read().is_empty() || tool_drag.get() {
             moving.set(false);
         } else {
             moving.set(true);
@@ -49,8 +65,8 @@ pub fn handlers(
 
         // match the number of touches to determine if it's a drag or zoom
         match tpos.read()[..] {
-            // drag
-            [old_pos] => {
+            // drag unless a tool is handling it
+            [old_pos] if !tool_drag.get() => {
                 // new delta
                 let touch = tl.get(0).unwrap();
                 let new_pos = touch_pos(&touch);
@@ -61,6 +77,7 @@ pub fn handlers(
                     *x += delta[0];
                     *y += delta[1];
                 });
+                momentum.update_value(|m| m.track(delta));
             }
             // zoom
             [old1, old2] => {
@@ -97,9 +114,21 @@ pub fn handlers(
         tpos.set(get_touch_positions(&tl));
     };
 
+    // glide when the last finger of a drag is lifted
+    let touchend = move |e: TouchEvent| {
+        let remaining = get_touch_positions(&e.touches());
+
+        if remaining.is_empty() && tpos.read().len() == 1 && moving.get() {
+            camera.glide(momentum.get_value().release());
+        }
+
+        tpos.set(remaining);
+    };
+
     TouchEventHandlers {
         touchstart,
         touchmove,
+        touchend,
     }
 }
 
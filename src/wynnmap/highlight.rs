@@ -0,0 +1,62 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::terr::Territory;
+
+use crate::{util::as_px, wynnmap::conns::bounds};
+
+/// Map layer outlining and tinting a set of territories in a single color
+#[component]
+pub fn HighlightView(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into)] highlighted: Signal<BTreeSet<Arc<str>>>,
+    /// Css color of the highlight
+    color: &'static str,
+) -> impl IntoView {
+    let bounds = Memo::new(move |_| bounds(&terrs.read()));
+
+    let viewbox = move || {
+        let b = bounds.get();
+        format!("{} {} {} {}", b.0, b.1, b.2, b.3)
+    };
+
+    let path = move || {
+        let terrs = terrs.read();
+        let mut path = String::new();
+
+        for terr in highlighted.read().iter().filter_map(|n| terrs.get(n)) {
+            let l = terr.location;
+
+            write!(
+                path,
+                "M{} {}h{}v{}h-{}z",
+                l.left_side(),
+                l.top_side(),
+                l.width(),
+                l.height(),
+                l.width()
+            )
+            .expect("Write should not fail");
+        }
+
+        path
+    };
+
+    view! {
+        <svg
+            class="connpath"
+            style:left=move || as_px(bounds.read().0)
+            style:top=move || as_px(bounds.read().1)
+            style:width=move || as_px(bounds.read().2)
+            style:height=move || as_px(bounds.read().3)
+            style:overflow="visible"
+            viewBox={viewbox}
+        >
+            <path d={path} style={format!("fill:{color};fill-opacity:0.35;stroke:{color};stroke-width:6;")} />
+        </svg>
+    }
+}
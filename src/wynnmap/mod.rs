@@ -5,27 +5,41 @@ use crate::wynnmap::{
     events::{mouse::MouseEventHandlers, pointer::PointerEventHandlers, touch::TouchEventHandlers},
 };
 
+mod camera;
+pub mod colormode;
 pub mod conns;
 pub mod context;
 mod events;
+pub mod highlight;
 pub mod maptile;
+pub mod routes;
+pub mod selection;
 pub mod terrs;
 mod util;
 
+pub use events::pointer::MapDrag;
+
 /// The minimum zoom level
 const ZOOM_MIN: f64 = 0.0625;
 /// The maximum zoom level
 const ZOOM_MAX: f64 = 64.0;
 
+/// The map view
+///
+/// When `ondrag` returns true for the start of a drag the drag is given to the callback instead of moving the map.
 #[component]
 pub fn WynnMap(
     children: Children,
     #[prop(optional)] onclick: Option<Callback<[i32; 2]>>,
+    #[prop(optional)] ondrag: Option<Callback<MapDrag, bool>>,
 ) -> impl IntoView {
     // is the map being moved currently
     let moving = RwSignal::new(false);
+    // is a drag currently handled by the ondrag callback
+    let tool_drag = RwSignal::new(false);
 
-    let MapPosition { position, zoom } = expect_context();
+    let camera = expect_context::<MapPosition>();
+    let MapPosition { position, zoom, .. } = camera;
 
     // are we currently transitioning? transitions can occur from zooming
     let transitioning = RwSignal::new(false);
@@ -35,21 +49,22 @@ pub fn WynnMap(
         start_mousemove,
         end_mousemove,
         wheel,
-    } = events::mouse::handlers(position, zoom, moving, transitioning);
+    } = events::mouse::handlers(camera, moving, tool_drag, transitioning);
 
     let TouchEventHandlers {
         touchstart,
         touchmove,
-    } = events::touch::handlers(position, zoom, moving);
+        touchend,
+    } = events::touch::handlers(camera, moving, tool_drag);
 
-    events::key::handlers(position, zoom, transitioning);
+    events::key::handlers(camera, transitioning);
 
     let PointerEventHandlers {
         pointermove,
         pointerdown,
         pointerup,
         pointerleave,
-    } = events::pointer::handlers(position, zoom, moving, onclick);
+    } = events::pointer::handlers(position, zoom, moving, tool_drag, onclick, ondrag);
 
     view! {
         // outermost container used for containing the map
@@ -64,6 +79,7 @@ pub fn WynnMap(
 
             on:touchstart=touchstart
             on:touchmove=touchmove
+            on:touchend=touchend
 
             on:pointermove=pointermove
             on:pointerdown=pointerdown
@@ -0,0 +1,125 @@
+use std::{collections::BTreeMap, fmt::Write, sync::Arc};
+
+use leptos::prelude::*;
+use wynnmap_types::{routing::TradeRoutes, terr::Territory};
+
+use crate::{util::as_px, wynnmap::conns::bounds};
+
+/// Map layer displaying the trade routes of a guild
+///
+/// All routes of the claim are drawn thinly while the route of the selected territory is highlighted. Territories
+/// which are cut off from the hq are outlined.
+#[component]
+pub fn TradeRouteView(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into)] routes: Signal<Option<TradeRoutes>>,
+    #[prop(into)] selected: Signal<Option<Arc<str>>>,
+) -> impl IntoView {
+    let bounds = Memo::new(move |_| bounds(&terrs.read()));
+
+    let viewbox = move || {
+        let b = bounds.get();
+        format!("{} {} {} {}", b.0, b.1, b.2, b.3)
+    };
+
+    let all_routes = move || {
+        routes
+            .read()
+            .as_ref()
+            .map(|r| route_tree_path(&terrs.read(), r))
+            .unwrap_or_default()
+    };
+
+    let selected_route = move || {
+        let routes = routes.read();
+        let route = routes
+            .as_ref()
+            .zip(selected.get())
+            .and_then(|(r, s)| r.route(&s));
+
+        route
+            .map(|r| route_path(&terrs.read(), &r))
+            .unwrap_or_default()
+    };
+
+    let cut_off = move || {
+        routes
+            .read()
+            .as_ref()
+            .map(|r| cut_off_path(&terrs.read(), r))
+            .unwrap_or_default()
+    };
+
+    view! {
+        <svg
+            class="connpath"
+            style:left=move || as_px(bounds.read().0)
+            style:top=move || as_px(bounds.read().1)
+            style:width=move || as_px(bounds.read().2)
+            style:height=move || as_px(bounds.read().3)
+            viewBox={viewbox}
+        >
+            <path d={all_routes} style="fill:none;stroke:#FBBF24;stroke-width:4;" stroke-linecap="round" />
+            <path d={selected_route} style="fill:none;stroke:black;stroke-width:12;" stroke-linecap="round" stroke-linejoin="round" />
+            <path d={selected_route} style="fill:none;stroke:#FBBF24;stroke-width:8;" stroke-linecap="round" stroke-linejoin="round" />
+            <path d={cut_off} style="fill:none;stroke:#DC2626;stroke-width:6;stroke-dasharray:12 8;" />
+        </svg>
+    }
+}
+
+/// Create the path for every route segment of the claim
+fn route_tree_path(terrs: &BTreeMap<Arc<str>, Territory>, routes: &TradeRoutes) -> String {
+    let mut path = String::new();
+
+    for (from, to) in &routes.next_hop {
+        if let (Some(from), Some(to)) = (terrs.get(from), terrs.get(to)) {
+            let start = from.location.get_midpoint();
+            let end = to.location.get_midpoint();
+
+            write!(path, "M{} {}L{} {}", start[0], start[1], end[0], end[1])
+                .expect("Write should not fail");
+        }
+    }
+
+    path
+}
+
+/// Create a single continuous path through the given territories
+fn route_path(terrs: &BTreeMap<Arc<str>, Territory>, route: &[Arc<str>]) -> String {
+    let mut path = String::new();
+
+    for (i, point) in route
+        .iter()
+        .filter_map(|n| terrs.get(n))
+        .map(|t| t.location.get_midpoint())
+        .enumerate()
+    {
+        let cmd = if i == 0 { 'M' } else { 'L' };
+
+        write!(path, "{cmd}{} {}", point[0], point[1]).expect("Write should not fail");
+    }
+
+    path
+}
+
+/// Create outlines for the territories which are cut off from the hq
+fn cut_off_path(terrs: &BTreeMap<Arc<str>, Territory>, routes: &TradeRoutes) -> String {
+    let mut path = String::new();
+
+    for terr in routes.cut_off.iter().filter_map(|n| terrs.get(n)) {
+        let l = terr.location;
+
+        write!(
+            path,
+            "M{} {}h{}v{}h-{}z",
+            l.left_side(),
+            l.top_side(),
+            l.width(),
+            l.height(),
+            l.width()
+        )
+        .expect("Write should not fail");
+    }
+
+    path
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    sync::Arc,
+};
+
+use leptos::prelude::*;
+use wynnmap_types::terr::Territory;
+
+use crate::{util::as_px, wynnmap::conns::bounds};
+
+/// Map layer highlighting selected territories and the outline of a selection being drawn
+#[component]
+pub fn SelectionView(
+    #[prop(into)] terrs: Signal<BTreeMap<Arc<str>, Territory>>,
+    #[prop(into)] selected: Signal<BTreeSet<Arc<str>>>,
+    /// Corners of the selection shape currently being drawn
+    #[prop(into)]
+    shape: Signal<Vec<[i32; 2]>>,
+) -> impl IntoView {
+    let bounds = Memo::new(move |_| bounds(&terrs.read()));
+
+    let viewbox = move || {
+        let b = bounds.get();
+        format!("{} {} {} {}", b.0, b.1, b.2, b.3)
+    };
+
+    let selected_path = move || {
+        let terrs = terrs.read();
+        let mut path = String::new();
+
+        for terr in selected.read().iter().filter_map(|n| terrs.get(n)) {
+            let l = terr.location;
+
+            write!(
+                path,
+                "M{} {}h{}v{}h-{}z",
+                l.left_side(),
+                l.top_side(),
+                l.width(),
+                l.height(),
+                l.width()
+            )
+            .expect("Write should not fail");
+        }
+
+        path
+    };
+
+    let shape_path = move || {
+        let mut path = String::new();
+
+        for (i, [x, y]) in shape.read().iter().enumerate() {
+            let cmd = if i == 0 { 'M' } else { 'L' };
+
+            write!(path, "{cmd}{x} {y}").expect("Write should not fail");
+        }
+
+        if !path.is_empty() {
+            path.push('Z');
+        }
+
+        path
+    };
+
+    view! {
+        <svg
+            class="connpath"
+            style:left=move || as_px(bounds.read().0)
+            style:top=move || as_px(bounds.read().1)
+            style:width=move || as_px(bounds.read().2)
+            style:height=move || as_px(bounds.read().3)
+            style:overflow="visible"
+            viewBox={viewbox}
+        >
+            <path d={selected_path} style="fill:#FFFFFF40;stroke:white;stroke-width:6;" />
+            <path d={shape_path} style="fill:#FFFFFF20;stroke:white;stroke-width:4;stroke-dasharray:12 8;" stroke-linejoin="round" />
+        </svg>
+    }
+}
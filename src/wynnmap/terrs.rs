@@ -1,4 +1,8 @@
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use jiff::Timestamp;
 use leptos::prelude::*;
@@ -9,10 +13,11 @@ use wynnmap_types::{
 };
 
 use crate::{
+    components::focus::use_hide_unfocused,
     sectimer::SecondTimer,
     settings::use_toggle,
     util::{as_px, fmt_time_short},
-    wynnmap::context::RelMousePos,
+    wynnmap::{colormode::ColorMode, context::RelMousePos},
 };
 
 #[component]
@@ -21,6 +26,10 @@ pub fn TerrView(
     #[prop(into)] state: Signal<BTreeMap<Arc<str>, TerrState>>,
     #[prop(optional)] hovered: RwSignal<Option<Arc<str>>>,
     #[prop(optional)] hide_timers: bool,
+    #[prop(into, optional)] color_mode: Signal<ColorMode>,
+    /// Tags of the focused guilds, while any are focused the territories of other guilds are dimmed or hidden
+    #[prop(into, optional)]
+    focus: Signal<BTreeSet<Arc<str>>>,
 ) -> impl IntoView {
     let pos = expect_context::<RelMousePos>();
 
@@ -49,7 +58,7 @@ pub fn TerrView(
                     });
 
                     view! {
-                        <Territory terr state hide_timers/>
+                        <Territory terr state hide_timers color_mode focus/>
                     }
                 }
             />
@@ -62,9 +71,23 @@ pub fn Territory(
     #[prop(into)] terr: Signal<Territory>,
     #[prop(into)] state: Signal<TerrState>,
     #[prop(optional)] hide_timers: bool,
+    #[prop(into, optional)] color_mode: Signal<ColorMode>,
+    /// Tags of the focused guilds
+    #[prop(into, optional)]
+    focus: Signal<BTreeSet<Arc<str>>>,
 ) -> impl IntoView {
+    let SecondTimer(now) = expect_context::<SecondTimer>();
+
     let col_rgb = move || {
-        let col = state.read().guild.get_color();
+        let mode = color_mode.get();
+        // only track the time for modes which need it
+        let now = if mode.uses_time() {
+            now.get()
+        } else {
+            now.get_untracked()
+        };
+
+        let col = mode.color(&terr.read(), &state.read(), now);
         format!("{} {} {}", col.0, col.1, col.2)
     };
 
@@ -74,12 +97,21 @@ pub fn Territory(
     let show_timers = use_toggle("timers", true);
     let use_transparency = use_toggle("use_transparency", true);
 
+    // territories of guilds which aren't focused are dimmed or hidden
+    let hide_unfocused = use_hide_unfocused();
+    let unfocused = Memo::new(move |_| {
+        let focus = focus.read();
+        !focus.is_empty() && !focus.contains(&state.read().guild.prefix)
+    });
+
     let location = move || terr.read().location;
     let namesize = Memo::new(move |_| (location().width() / 3).min(40));
 
     view! {
         <div class="guildterr" class:hq={move || state.read().hq}
             class:guildterr-notrans=move || !use_transparency.get()
+            class:guildterr-unfocused=move || unfocused.get() && !hide_unfocused.get()
+            class:hidden=move || unfocused.get() && hide_unfocused.get()
             style:width=move || as_px(location().width())
             style:height=move || as_px(location().height())
             style:top=move || as_px(location().top_side())
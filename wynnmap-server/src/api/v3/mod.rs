@@ -1,2 +1,4 @@
 pub mod gather;
+pub mod plans;
+pub mod sessions;
 pub mod territories;
@@ -0,0 +1,106 @@
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+use axum::{
+    Json,
+    extract::{ConnectInfo, DefaultBodyLimit, Path, Query, State},
+    http::{HeaderMap, header},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use jiff::{SignedDuration, Timestamp};
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::plan_store::{PlanError, PlanStore};
+
+/// Maximum time a stored plan is cached for in seconds
+const MAX_CACHE_AGE: i64 = 3600;
+
+pub fn router(store: Arc<PlanStore>) -> axum::Router {
+    axum::Router::new()
+        .route("/", post(store_plan))
+        .route("/{id}", get(load_plan))
+        .layer(DefaultBodyLimit::max(store.max_size()))
+        .with_state(store)
+}
+
+impl IntoResponse for PlanError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::NotFound => StatusCode::NOT_FOUND,
+            Self::InvalidPayload => StatusCode::BAD_REQUEST,
+            Self::StoreFull => StatusCode::INSUFFICIENT_STORAGE,
+            Self::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            Self::Io(_) | Self::Json(_) => {
+                tracing::error!(error = ?self, "Error occured while accessing stored plans");
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        };
+
+        (status, Json(self.to_string())).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StoreQuery {
+    /// Number of days after which the plan is deleted
+    expires: Option<u32>,
+}
+
+/// The ip of the client from the configured proxy header or the connection
+fn client_ip(store: &PlanStore, headers: &HeaderMap, addr: SocketAddr) -> IpAddr {
+    store
+        .ip_header()
+        .and_then(|name| headers.get(name))
+        .and_then(|v| v.to_str().ok())
+        // proxies append to the header so the first entry is the original client
+        .and_then(|v| v.split(',').next())
+        .and_then(|ip| ip.trim().parse().ok())
+        .unwrap_or_else(|| addr.ip())
+}
+
+#[tracing::instrument(skip(store, headers, payload))]
+async fn store_plan(
+    State(store): State<Arc<PlanStore>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(query): Query<StoreQuery>,
+    payload: String,
+) -> Result<impl IntoResponse, PlanError> {
+    let expires_in = query
+        .expires
+        .map(|d| SignedDuration::from_hours(i64::from(d) * 24));
+
+    let client = client_ip(&store, &headers, addr);
+    let stored = store.insert(client, &payload, expires_in).await?;
+
+    Ok((StatusCode::CREATED, Json(stored)))
+}
+
+#[tracing::instrument(skip(store))]
+async fn load_plan(
+    State(store): State<Arc<PlanStore>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, PlanError> {
+    let (payload, expires) = store.get(&id).await?;
+
+    // plans are addressed by their content so they never change, but they mustn't stay cached after expiring
+    let max_age = expires.map_or(MAX_CACHE_AGE, |e| {
+        e.duration_since(Timestamp::now())
+            .as_secs()
+            .clamp(0, MAX_CACHE_AGE)
+    });
+
+    let resp_headers = [
+        (header::CACHE_CONTROL, format!("public, max-age={max_age}")),
+        (
+            header::CONTENT_TYPE,
+            String::from("text/plain; charset=utf-8"),
+        ),
+    ];
+
+    Ok((resp_headers, payload.to_string()))
+}
@@ -0,0 +1,229 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use axum::{
+    extract::{
+        Path, State, WebSocketUpgrade,
+        ws::{Message, WebSocket},
+    },
+    response::IntoResponse,
+    routing::get,
+};
+use reqwest::StatusCode;
+use tokio::{
+    select,
+    sync::broadcast::{self, error::RecvError},
+    time::timeout,
+};
+use wynnmap_types::{
+    encoding,
+    plan::is_valid_plan_id,
+    session::{
+        MAX_PARTICIPANTS, Participant, Presence, SessionClientMessage, SessionServerMessage,
+        is_valid_name,
+    },
+};
+
+use crate::{
+    AnyError,
+    state::{SessionRoom, SessionState},
+};
+
+/// Maximum size of a message sent by a participant
+const MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Maximum size of a message sent by a participant once decompressed
+const MAX_DECODED_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Maximum number of open session rooms
+const MAX_ROOMS: usize = 256;
+
+pub fn router(state: Arc<SessionState>) -> axum::Router {
+    axum::Router::new()
+        .route("/{room}/ws", get(ws_handler))
+        .with_state(state)
+}
+
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    Path(room): Path<String>,
+    State(state): State<Arc<SessionState>>,
+) -> impl IntoResponse {
+    // room ids follow the same rules as plan ids
+    if !is_valid_plan_id(&room) {
+        return (StatusCode::BAD_REQUEST, "Invalid room id").into_response();
+    }
+
+    let room: Arc<str> = Arc::from(room);
+
+    ws.max_message_size(MAX_MESSAGE_SIZE)
+        .on_upgrade(move |s| async move {
+            if let Err(e) = handle_socket(s, &state, room).await {
+                tracing::error!("Error handling session socket: {:?}", e);
+            }
+        })
+        .into_response()
+}
+
+/// Send a message to every participant of the room
+fn broadcast(room: &SessionRoom, msg: &SessionServerMessage) -> Result<(), AnyError> {
+    // sending only fails when nobody is listening which is fine
+    let _ = room.bc_bytes.send(Arc::new(encoding::encode_data(msg)?));
+
+    Ok(())
+}
+
+async fn handle_socket(
+    mut socket: WebSocket,
+    state: &SessionState,
+    room_id: Arc<str>,
+) -> Result<(), AnyError> {
+    // the first message has to be a join message
+    let Ok(Some(Ok(Message::Binary(data)))) = timeout(Duration::from_secs(10), socket.recv()).await
+    else {
+        return Ok(());
+    };
+    let SessionClientMessage::Join(name, plan) =
+        encoding::decode_data_limited(&data, MAX_DECODED_SIZE)?
+    else {
+        return Ok(());
+    };
+
+    if !is_valid_name(&name) || !plan.is_within_limits() {
+        return Ok(());
+    }
+
+    let (id, mut bc_recv, welcome) = {
+        let mut rooms = state.rooms.lock().await;
+
+        if !rooms.contains_key(&room_id) && rooms.len() >= MAX_ROOMS {
+            return Ok(());
+        }
+
+        // the first participant starts the session with their plan
+        let room = rooms.entry(room_id.clone()).or_insert_with(|| SessionRoom {
+            plan,
+            participants: BTreeMap::new(),
+            next_id: 0,
+            bc_bytes: broadcast::channel(256).0,
+        });
+
+        if room.participants.len() >= MAX_PARTICIPANTS {
+            return Ok(());
+        }
+
+        let id = room.next_id;
+        room.next_id += 1;
+
+        let participant = Participant {
+            id,
+            name,
+            presence: Presence::default(),
+        };
+
+        let welcome = SessionServerMessage::Welcome(
+            id,
+            room.plan.clone(),
+            room.participants.values().cloned().collect(),
+        );
+
+        room.participants.insert(id, participant.clone());
+        broadcast(room, &SessionServerMessage::Joined(participant))?;
+
+        (id, room.bc_bytes.subscribe(), welcome)
+    };
+
+    let res = async {
+        socket
+            .send(Message::Binary(encoding::encode_data(&welcome)?.into()))
+            .await?;
+
+        relay(&mut socket, state, &room_id, id, &mut bc_recv).await
+    }
+    .await;
+
+    let mut rooms = state.rooms.lock().await;
+
+    if let Some(room) = rooms.get_mut(&room_id) {
+        room.participants.remove(&id);
+
+        if room.participants.is_empty() {
+            rooms.remove(&room_id);
+        } else {
+            broadcast(room, &SessionServerMessage::Left(id))?;
+        }
+    }
+
+    res
+}
+
+/// Apply the messages of a participant and send them the messages of the room until they disconnect
+async fn relay(
+    socket: &mut WebSocket,
+    state: &SessionState,
+    room_id: &str,
+    id: u32,
+    bc_recv: &mut broadcast::Receiver<Arc<Vec<u8>>>,
+) -> Result<(), AnyError> {
+    loop {
+        select! {
+            s = socket.recv() => {
+                let Some(Ok(msg)) = s else {
+                    break;
+                };
+
+                match msg {
+                    Message::Binary(data) => {
+                        let msg: SessionClientMessage =
+                            encoding::decode_data_limited(&data, MAX_DECODED_SIZE)?;
+
+                        let mut rooms = state.rooms.lock().await;
+                        let Some(room) = rooms.get_mut(room_id) else {
+                            break;
+                        };
+
+                        match msg {
+                            SessionClientMessage::Join(..) => {}
+                            SessionClientMessage::Op(op) => {
+                                // operations are applied and relayed in a single order so all participants converge
+                                if room.plan.apply(&op) {
+                                    broadcast(room, &SessionServerMessage::Op(id, op))?;
+                                }
+                            }
+                            SessionClientMessage::Presence(presence) if !presence.is_within_limits() => {}
+                            SessionClientMessage::Presence(presence) => {
+                                if let Some(p) = room.participants.get_mut(&id) {
+                                    p.presence = presence.clone();
+                                }
+
+                                broadcast(room, &SessionServerMessage::Presence(id, presence))?;
+                            }
+                        }
+                    }
+                    Message::Ping(data) => {
+                        if data.len() > 32 { break; }
+                        socket.send(Message::Pong(data)).await?;
+                    }
+                    Message::Close(frame) => {
+                        let _ = timeout(Duration::from_secs(5), socket.send(Message::Close(frame))).await;
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+
+            msg = bc_recv.recv() => {
+                match msg {
+                    Ok(msg) => {
+                        socket
+                            .send(Message::Binary((*msg).clone().into()))
+                            .await?;
+                    }
+                    // a participant which missed operations has to rejoin to get the full plan again
+                    Err(RecvError::Lagged(_) | RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
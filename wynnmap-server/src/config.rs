@@ -7,6 +7,8 @@ pub struct Config {
     pub server: ServerConfig,
     pub client: ClientConfig,
     pub images: ImagesConfig,
+    #[serde(default)]
+    pub plans: PlansConfig,
     pub otel: Option<OtelConfig>,
 }
 
@@ -28,6 +30,36 @@ pub struct ImagesConfig {
     pub use_webp: bool,
 }
 
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct PlansConfig {
+    /// Directory where stored plans are saved
+    pub dir: Arc<str>,
+    /// Maximum size of a stored plan in bytes
+    pub max_size: usize,
+    /// Maximum number of days a plan is kept for, plans are kept forever if not set
+    pub max_expiry_days: Option<u32>,
+    /// Maximum total size of all stored plans in bytes
+    pub max_total_size: u64,
+    /// Maximum number of plans a single client may store per hour
+    pub max_per_hour: u32,
+    /// Header containing the client ip when running behind a reverse proxy, such as "X-Forwarded-For"
+    pub ip_header: Option<Arc<str>>,
+}
+
+impl Default for PlansConfig {
+    fn default() -> Self {
+        Self {
+            dir: Arc::from("./plans"),
+            max_size: 64 * 1024,
+            max_expiry_days: None,
+            max_total_size: 1024 * 1024 * 1024,
+            max_per_hour: 30,
+            ip_header: None,
+        }
+    }
+}
+
 #[derive(Clone, Deserialize)]
 pub struct OtelConfig {
     pub endpoint: Arc<str>,
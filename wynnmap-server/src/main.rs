@@ -1,4 +1,6 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use axum::http::Method;
 use axum::response::IntoResponse;
@@ -6,7 +8,7 @@ use axum::{Json, Router, middleware};
 use jiff::Timestamp;
 use jiff::fmt::rfc2822::DateTimePrinter;
 use reqwest::StatusCode;
-use state::ImageState;
+use state::{ImageState, SessionState};
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::compression::CompressionLayer;
@@ -14,6 +16,7 @@ use tower_http::cors::{self, CorsLayer};
 use tower_http::services::{ServeDir, ServeFile};
 use tracing::info;
 
+use crate::plan_store::PlanStore;
 use crate::trackers::gather::GatherSpotsTracker;
 use crate::trackers::guilds::GuildTracker;
 use crate::trackers::images::ImageTracker;
@@ -24,6 +27,7 @@ mod config;
 mod etag;
 mod file_cache;
 mod otel;
+mod plan_store;
 mod state;
 mod trackers;
 
@@ -46,6 +50,8 @@ async fn main() {
     let guild_state = GuildTracker::with_config(&config).run();
     let terr_state = TerritoryTracker::with_config(&config, &guild_state).run();
     let gather_state = GatherSpotsTracker::with_config(&config).run();
+    let plan_store = PlanStore::with_config(&config).run();
+    let session_state = Arc::new(SessionState::default());
 
     let cors = CorsLayer::new()
         .allow_origin(cors::Any)
@@ -66,6 +72,8 @@ async fn main() {
                     Router::new()
                         .nest("/terr", api::v3::territories::router(terr_state))
                         .nest("/gather", api::v3::gather::router(gather_state))
+                        .nest("/plans", api::v3::plans::router(plan_store))
+                        .nest("/sessions", api::v3::sessions::router(session_state))
                         .fallback(api_404),
                 )
                 .fallback(api_404),
@@ -90,7 +98,12 @@ async fn main() {
         .unwrap();
 
     info!("Listning on {}:{}", config.server.bind, config.server.port);
-    axum::serve(listener, app).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .unwrap();
 }
 
 async fn api_404() -> impl IntoResponse {
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use jiff::{SignedDuration, Timestamp};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+use tracing::{error, info};
+use wynnmap_types::plan::{MAX_PLAN_ID_LEN, StoredPlan, is_valid_plan_id};
+
+use crate::config::Config;
+
+/// Length of newly generated plan ids, longer ids are only used on hash collisions
+const PLAN_ID_LEN: usize = 10;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PlanError {
+    #[error("plan not found")]
+    NotFound,
+    #[error("plan payload is empty or contains invalid characters")]
+    InvalidPayload,
+    #[error("plan storage is full")]
+    StoreFull,
+    #[error("too many plans stored, try again later")]
+    RateLimited,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A stored plan as saved on disk
+#[derive(Serialize, Deserialize)]
+struct PlanFile {
+    payload: Arc<str>,
+    created: Timestamp,
+    expires: Option<Timestamp>,
+}
+
+impl PlanFile {
+    fn is_expired(&self) -> bool {
+        self.expires.is_some_and(|e| e <= Timestamp::now())
+    }
+}
+
+/// Plans stored on the local disk, addressed by the hash of their content
+pub struct PlanStore {
+    dir: PathBuf,
+    max_size: usize,
+    max_expiry: Option<SignedDuration>,
+    max_total_size: u64,
+    max_per_hour: u32,
+    ip_header: Option<Arc<str>>,
+
+    /// Total size of the plan files, held while writing plans so that identical plans stored at the same time don't race
+    write_lock: Mutex<u64>,
+    /// Number of plans stored by each client since the start of the hour
+    stored_by: Mutex<HashMap<IpAddr, u32>>,
+}
+
+impl PlanStore {
+    pub fn with_config(config: &Config) -> Self {
+        Self {
+            dir: PathBuf::from(config.plans.dir.as_ref()),
+            max_size: config.plans.max_size,
+            max_expiry: config
+                .plans
+                .max_expiry_days
+                .map(|d| SignedDuration::from_hours(i64::from(d) * 24)),
+            max_total_size: config.plans.max_total_size,
+            max_per_hour: config.plans.max_per_hour,
+            ip_header: config.plans.ip_header.clone(),
+            write_lock: Mutex::new(0),
+            stored_by: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create the plan directory and start deleting expired plans periodically
+    pub fn run(self) -> Arc<Self> {
+        let store = Arc::new(self);
+        let store2 = store.clone();
+
+        tokio::spawn(async move {
+            if let Err(e) = tokio::fs::create_dir_all(&store.dir).await {
+                error!(error = ?e, "Failed to create the plan directory");
+                return;
+            }
+
+            loop {
+                store.stored_by.lock().await.clear();
+
+                match store.remove_expired().await {
+                    Ok(0) => {}
+                    Ok(n) => info!(count = n, "Removed expired plans"),
+                    Err(e) => error!(error = ?e, "Error occured while removing expired plans"),
+                }
+
+                tokio::time::sleep(Duration::from_hours(1)).await;
+            }
+        });
+
+        store2
+    }
+
+    /// Maximum size of a plan payload in bytes
+    pub const fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Header containing the client ip when running behind a reverse proxy
+    pub fn ip_header(&self) -> Option<&str> {
+        self.ip_header.as_deref()
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    async fn read(&self, id: &str) -> Result<Option<PlanFile>, PlanError> {
+        match tokio::fs::read(self.path(id)).await {
+            Ok(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Store a plan returning its id
+    ///
+    /// Storing a plan which is already stored returns the existing id and keeps the later of the two expiry times.
+    /// The requested expiry is limited to the configured maximum.
+    pub async fn insert(
+        &self,
+        client: IpAddr,
+        payload: &str,
+        expires_in: Option<SignedDuration>,
+    ) -> Result<StoredPlan, PlanError> {
+        if payload.is_empty() || !payload.bytes().all(|b| b.is_ascii_graphic()) {
+            return Err(PlanError::InvalidPayload);
+        }
+
+        {
+            let mut stored_by = self.stored_by.lock().await;
+            let count = stored_by.entry(client).or_default();
+
+            if *count >= self.max_per_hour {
+                return Err(PlanError::RateLimited);
+            }
+
+            *count += 1;
+        }
+
+        let now = Timestamp::now();
+        let expires_in = match (expires_in, self.max_expiry) {
+            (Some(req), Some(max)) => Some(req.min(max)),
+            (req, max) => req.or(max),
+        };
+        let expires = expires_in.and_then(|d| now.checked_add(d).ok());
+
+        let hash = BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(payload.as_bytes()));
+
+        let mut total_size = self.write_lock.lock().await;
+
+        // take a longer prefix of the hash on the rare collision with a different plan
+        for len in PLAN_ID_LEN..=MAX_PLAN_ID_LEN {
+            let id = &hash[..len];
+
+            let file = match self.read(id).await? {
+                Some(file) if *file.payload != *payload && !file.is_expired() => continue,
+                Some(file) if !file.is_expired() => PlanFile {
+                    expires: file.expires.zip(expires).map(|(a, b)| a.max(b)),
+                    ..file
+                },
+                _ => PlanFile {
+                    payload: Arc::from(payload),
+                    created: now,
+                    expires,
+                },
+            };
+
+            let data = serde_json::to_vec(&file)?;
+            let old_size = tokio::fs::metadata(self.path(id))
+                .await
+                .map_or(0, |m| m.len());
+            let new_total = total_size.saturating_sub(old_size) + data.len() as u64;
+
+            if new_total > self.max_total_size {
+                return Err(PlanError::StoreFull);
+            }
+
+            tokio::fs::write(self.path(id), data).await?;
+            *total_size = new_total;
+
+            return Ok(StoredPlan {
+                id: Arc::from(id),
+                expires: file.expires,
+            });
+        }
+
+        Err(PlanError::InvalidPayload)
+    }
+
+    /// Get the payload of a stored plan and the time it expires at
+    pub async fn get(&self, id: &str) -> Result<(Arc<str>, Option<Timestamp>), PlanError> {
+        if !is_valid_plan_id(id) {
+            return Err(PlanError::NotFound);
+        }
+
+        match self.read(id).await? {
+            Some(file) if !file.is_expired() => Ok((file.payload, file.expires)),
+            _ => Err(PlanError::NotFound),
+        }
+    }
+
+    /// Delete every expired plan returning the number of deleted plans
+    ///
+    /// The total size of the remaining plans is counted again while going through them.
+    async fn remove_expired(&self) -> Result<usize, PlanError> {
+        let mut total_size = self.write_lock.lock().await;
+
+        let mut entries = tokio::fs::read_dir(&self.dir).await?;
+        let mut removed = 0;
+        let mut total = 0;
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+
+            if let Ok(Some(file)) = self.read(id).await
+                && file.is_expired()
+            {
+                tokio::fs::remove_file(&path).await?;
+                removed += 1;
+            } else {
+                total += entry.metadata().await?.len();
+            }
+        }
+
+        *total_size = total;
+
+        Ok(removed)
+    }
+}
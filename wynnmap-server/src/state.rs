@@ -1,13 +1,17 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
 
 use axum::body::Bytes;
 use jiff::Timestamp;
 use opentelemetry::metrics::UpDownCounter;
-use tokio::sync::{RwLock, broadcast};
+use tokio::sync::{Mutex, RwLock, broadcast};
 use wynnmap_types::{
     gather::GatherSpots,
     guild::Guild,
     maptile::MapTile,
+    session::{Participant, SessionPlan},
     terr::{TerrState, TerrTimestamps, Territory},
 };
 
@@ -51,3 +55,19 @@ pub struct GatherState {
     pub nodes: RwLock<Arc<GatherSpots>>,
     pub etag: RwLock<Arc<str>>,
 }
+
+#[derive(Debug, Default)]
+pub struct SessionState {
+    /// Planning session rooms by their id
+    pub rooms: Mutex<HashMap<Arc<str>, SessionRoom>>,
+}
+
+#[derive(Debug)]
+pub struct SessionRoom {
+    pub plan: SessionPlan,
+    pub participants: BTreeMap<u32, Participant>,
+    pub next_id: u32,
+
+    /// A broadcast sender for encoded session messages
+    pub bc_bytes: broadcast::Sender<Arc<Vec<u8>>>,
+}
@@ -0,0 +1,161 @@
+//! Attack route finding
+//!
+//! Finds the cheapest route over the territory connections from any territory of a guild to a target territory. Every
+//! territory entered on the way has to be taken, so the cost of a route grows with its hops, with the enemy territories
+//! on it and with how strong those territories are estimated to be.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap},
+    sync::Arc,
+};
+
+use jiff::{SignedDuration, Timestamp};
+
+use crate::{
+    frontline::{cooldown_left, estimated_tier},
+    graph::TerrGraph,
+    guild::Guild,
+    terr::{TerrState, Territory},
+    tier::WynnTier,
+};
+
+/// Cost of entering any territory
+const HOP_COST: u32 = 10;
+/// Extra cost of entering a territory owned by another guild
+const ENEMY_COST: u32 = 10;
+/// Extra cost of entering an enemy territory for every estimated tier above the lowest
+const TIER_COST: u32 = 5;
+
+/// A territory which has to be taken on an attack route
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttackHop {
+    pub name: Arc<str>,
+    pub owner: Guild,
+    /// Whether the territory is owned by another guild instead of nobody
+    pub enemy: bool,
+    /// Tier estimated from the time held
+    pub tier: WynnTier,
+    /// Time left on the attack cooldown, zero if the territory can be attacked
+    pub cooldown: SignedDuration,
+}
+
+/// The best route for attacking a territory
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct AttackRoute {
+    /// The territory of the attacking guild the route starts from
+    pub start: Arc<str>,
+    /// Territories taken along the route ending with the target
+    pub hops: Vec<AttackHop>,
+    /// Total cost of the route, lower is better
+    pub score: u32,
+}
+
+impl AttackRoute {
+    /// Number of enemy territories on the route
+    pub fn enemies(&self) -> usize {
+        self.hops.iter().filter(|h| h.enemy).count()
+    }
+}
+
+/// Cost of taking a territory
+fn hop_cost(state: &TerrState, now: Timestamp) -> u32 {
+    if state.guild == Guild::default() {
+        return HOP_COST;
+    }
+
+    HOP_COST + ENEMY_COST + TIER_COST * estimated_tier(state, now) as u32
+}
+
+/// Find the cheapest route from any territory owned by the guild with the given prefix to the target
+///
+/// Returns `None` if the guild owns the target, owns no territories or can't reach the target.
+pub fn attack_route(
+    terrs: &BTreeMap<Arc<str>, Territory>,
+    state: &BTreeMap<Arc<str>, TerrState>,
+    prefix: &str,
+    target: &str,
+    now: Timestamp,
+) -> Option<AttackRoute> {
+    let graph = TerrGraph::new(terrs);
+    let target = graph.index_of(target)?;
+
+    let terr_state = |i: usize| state.get(graph.name(i)).cloned().unwrap_or_default();
+    let owned = |i: usize| {
+        state
+            .get(graph.name(i))
+            .is_some_and(|s| &*s.guild.prefix == prefix)
+    };
+
+    if owned(target) {
+        return None;
+    }
+
+    // dijkstra from every owned territory at once
+    let mut cost = vec![u32::MAX; graph.len()];
+    let mut prev = vec![None; graph.len()];
+    let mut queue = BinaryHeap::new();
+
+    for i in (0..graph.len()).filter(|i| owned(*i)) {
+        cost[i] = 0;
+        queue.push(Reverse((0, i)));
+    }
+
+    while let Some(Reverse((c, cur))) = queue.pop() {
+        if c > cost[cur] {
+            continue;
+        }
+
+        if cur == target {
+            break;
+        }
+
+        for &next in graph.neighbours(cur) {
+            // routes can't pass through the guild's own territories again
+            if owned(next) {
+                continue;
+            }
+
+            // territories without a state are unclaimed
+            let nc = c + state
+                .get(graph.name(next))
+                .map_or(HOP_COST, |s| hop_cost(s, now));
+
+            if nc < cost[next] {
+                cost[next] = nc;
+                prev[next] = Some(cur);
+                queue.push(Reverse((nc, next)));
+            }
+        }
+    }
+
+    if cost[target] == u32::MAX {
+        return None;
+    }
+
+    // walk the route backwards to the owned territory it starts from
+    let mut hops = Vec::new();
+    let mut cur = target;
+
+    while let Some(p) = prev[cur] {
+        let s = terr_state(cur);
+
+        hops.push(AttackHop {
+            name: graph.name(cur).clone(),
+            enemy: s.guild != Guild::default(),
+            tier: estimated_tier(&s, now),
+            cooldown: cooldown_left(&s, now),
+            owner: s.guild,
+        });
+
+        cur = p;
+    }
+
+    hops.reverse();
+
+    Some(AttackRoute {
+        start: graph.name(cur).clone(),
+        hops,
+        score: cost[target],
+    })
+}
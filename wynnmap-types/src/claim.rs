@@ -0,0 +1,247 @@
+//! Guild claim analysis
+//!
+//! Summarises a guild claim beyond its territory count: resource production, how fragmented it is, where the hq is
+//! best placed and which territories border other guilds. Also generates claims around a chosen hq.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{
+    graph::{EXTERNALS_RADIUS, TerrGraph},
+    guild::Guild,
+    resources::BaseResGen,
+    terr::{TerrState, Territory},
+    tower::stat_multiplier,
+};
+
+/// The best hq territory of a claim
+#[derive(Clone, Debug, PartialEq)]
+pub struct HqCandidate {
+    pub name: Arc<str>,
+    /// Number of connections owned by the guild
+    pub conns: usize,
+    /// Number of externals owned by the guild
+    pub externals: usize,
+    /// Tower stat multiplier of the territory as an hq
+    pub multiplier: f64,
+}
+
+/// Analysis of a single guild claim
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClaimAnalysis {
+    /// Number of territories in the claim
+    pub territories: usize,
+    /// Total base resource production of the claim
+    pub production: BaseResGen,
+    /// Number of disconnected parts the claim is split into
+    pub components: usize,
+    /// The territory with the most externals, `None` if the claim is empty
+    pub hq_candidate: Option<HqCandidate>,
+    /// Territories of the claim connected to territories owned by other guilds
+    pub borders: BTreeSet<Arc<str>>,
+}
+
+impl ClaimAnalysis {
+    /// Analyse the claim of the guild with the given prefix
+    pub fn for_guild(
+        terrs: &BTreeMap<Arc<str>, Territory>,
+        state: &BTreeMap<Arc<str>, TerrState>,
+        prefix: &str,
+    ) -> Self {
+        let owner = |n: &str| state.get(n).map(|s| &*s.guild.prefix);
+        let claim = TerrGraph::guild(terrs, state, prefix);
+        let graph = TerrGraph::new(terrs);
+        let nobody = Guild::default();
+
+        let production = claim.names().iter().map(|n| terrs[n].generates).sum();
+
+        let hq_candidate = claim
+            .names()
+            .iter()
+            .map(|name| {
+                let owned = |n: &Arc<str>| owner(n) == Some(prefix);
+
+                let conns = graph.connections(name).iter().filter(|n| owned(n)).count();
+                let externals = graph
+                    .externals(name, EXTERNALS_RADIUS)
+                    .iter()
+                    .filter(|n| owned(n))
+                    .count();
+
+                HqCandidate {
+                    name: name.clone(),
+                    conns,
+                    externals,
+                    multiplier: stat_multiplier(true, conns as i32, externals as i32),
+                }
+            })
+            .max_by(|a, b| {
+                (a.externals, a.conns)
+                    .cmp(&(b.externals, b.conns))
+                    // prefer the alphabetically first territory on ties
+                    .then_with(|| b.name.cmp(&a.name))
+            });
+
+        let borders = claim
+            .names()
+            .iter()
+            .filter(|name| {
+                graph.connections(name).iter().any(|n| {
+                    state
+                        .get(n)
+                        .is_some_and(|s| *s.guild.prefix != *prefix && s.guild != nobody)
+                })
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            territories: claim.len(),
+            production,
+            components: claim.components().len(),
+            hq_candidate,
+            borders,
+        }
+    }
+}
+
+/// Maximum number of territory swaps done when improving a generated claim
+const MAX_SWAPS: usize = 1000;
+
+/// Weights of each resource when scoring territories
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ResourceWeights {
+    pub emerald: f64,
+    pub ore: f64,
+    pub crop: f64,
+    pub fish: f64,
+    pub wood: f64,
+}
+
+impl Default for ResourceWeights {
+    fn default() -> Self {
+        Self {
+            emerald: 1.0,
+            ore: 1.0,
+            crop: 1.0,
+            fish: 1.0,
+            wood: 1.0,
+        }
+    }
+}
+
+impl ResourceWeights {
+    /// The weighted sum of the resources
+    #[inline]
+    pub fn score(&self, res: BaseResGen) -> f64 {
+        self.emerald * f64::from(res.emerald)
+            + self.ore * f64::from(res.ore)
+            + self.crop * f64::from(res.crop)
+            + self.fish * f64::from(res.fish)
+            + self.wood * f64::from(res.wood)
+    }
+}
+
+/// Options for generating a claim
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClaimOptions {
+    /// The hq territory the claim is built around
+    pub hq: Arc<str>,
+    /// Number of territories in the claim including the hq
+    pub size: usize,
+    pub weights: ResourceWeights,
+    /// Score of each external of the hq in the claim, on the same scale as the weighted resources
+    pub external_weight: f64,
+    /// Territories which may not be part of the claim
+    pub avoid: BTreeSet<Arc<str>>,
+}
+
+/// Generate a connected claim around the hq maximising the weighted resources and the hq externals
+///
+/// The claim is grown greedily from the hq and then improved by swapping its worst territories for better ones along
+/// its edge while it stays connected. Returns an empty claim if the hq doesn't exist or is avoided. The claim may be
+/// smaller than requested if the hq can't reach enough territories.
+pub fn build_claim(
+    terrs: &BTreeMap<Arc<str>, Territory>,
+    options: &ClaimOptions,
+) -> BTreeSet<Arc<str>> {
+    let hq = &options.hq;
+
+    if !terrs.contains_key(hq) || options.avoid.contains(hq) {
+        return BTreeSet::new();
+    }
+
+    let graph = TerrGraph::filtered(terrs, |n| !options.avoid.contains(n));
+    // externals are based on the connections of every territory even if some of them are avoided
+    let externals = TerrGraph::new(terrs).externals(hq, EXTERNALS_RADIUS);
+    let distance = graph.distances(hq);
+
+    let value = |n: &Arc<str>| {
+        let bonus = if externals.contains(n) {
+            options.external_weight
+        } else {
+            0.0
+        };
+
+        options.weights.score(terrs[n].generates) + bonus
+    };
+
+    // best candidate first, closer territories win ties
+    let better = |a: &Arc<str>, b: &Arc<str>| {
+        value(a)
+            .total_cmp(&value(b))
+            .then_with(|| distance[b].cmp(&distance[a]))
+            .then_with(|| b.cmp(a))
+    };
+
+    let frontier = |claim: &BTreeSet<Arc<str>>| {
+        claim
+            .iter()
+            .flat_map(|n| graph.connections(n))
+            .filter(|n| !claim.contains(n))
+            .collect::<BTreeSet<_>>()
+    };
+
+    let mut claim = BTreeSet::from([hq.clone()]);
+
+    while claim.len() < options.size
+        && let Some(next) = frontier(&claim).into_iter().max_by(|a, b| better(a, b))
+    {
+        claim.insert(next);
+    }
+
+    for _ in 0..MAX_SWAPS {
+        let cuts = TerrGraph::filtered(terrs, |n| claim.contains(n)).articulation_points();
+
+        let Some(worst) = claim
+            .iter()
+            .filter(|n| *n != hq && !cuts.contains(*n))
+            .min_by(|a, b| better(a, b))
+            .cloned()
+        else {
+            break;
+        };
+
+        claim.remove(&worst);
+
+        let best = frontier(&claim)
+            .into_iter()
+            .filter(|n| *n != worst)
+            .max_by(|a, b| better(a, b))
+            .filter(|n| value(n) > value(&worst));
+
+        match best {
+            Some(best) => {
+                claim.insert(best);
+            }
+            None => {
+                claim.insert(worst);
+                break;
+            }
+        }
+    }
+
+    claim
+}
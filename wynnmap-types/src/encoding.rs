@@ -1,3 +1,5 @@
+use std::io::Read;
+
 use serde::{
     Serialize,
     de::{DeserializeOwned, Error as _},
@@ -15,3 +17,27 @@ pub fn decode_data<T: DeserializeOwned>(data: &[u8]) -> Result<T, rmp_serde::dec
 
     rmp_serde::from_slice(&data)
 }
+
+/// Decode data which may be at most `limit` bytes large once decompressed
+///
+/// Data which decompresses to more than the limit is rejected without decompressing the rest of it.
+pub fn decode_data_limited<T: DeserializeOwned>(
+    data: &[u8],
+    limit: u64,
+) -> Result<T, rmp_serde::decode::Error> {
+    let decoder = zstd::stream::Decoder::new(data).map_err(rmp_serde::decode::Error::custom)?;
+
+    let mut buf = Vec::new();
+    decoder
+        .take(limit + 1)
+        .read_to_end(&mut buf)
+        .map_err(rmp_serde::decode::Error::custom)?;
+
+    if buf.len() as u64 > limit {
+        return Err(rmp_serde::decode::Error::custom(
+            "decompressed data exceeds the size limit",
+        ));
+    }
+
+    rmp_serde::from_slice(&buf)
+}
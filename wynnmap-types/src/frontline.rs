@@ -0,0 +1,104 @@
+//! Frontline of a guild claim
+//!
+//! The frontline is every territory of another guild directly connected to the claim. Its territories are ranked by
+//! how good of an attack target they are: territories off their attack cooldown come first, then territories held for
+//! a shorter time since they are weaker, then territories touching more of the claim.
+
+use std::{cmp::Reverse, collections::BTreeMap, sync::Arc};
+
+use jiff::{SignedDuration, Timestamp};
+
+use crate::{
+    graph::TerrGraph,
+    guild::Guild,
+    terr::{TerrState, Territory},
+    tier::WynnTier,
+};
+
+/// Time after a territory is taken during which it can't be attacked
+pub const ATTACK_COOLDOWN: SignedDuration = SignedDuration::from_mins(10);
+
+/// A territory on the frontline of a claim
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FrontlineTerr {
+    pub name: Arc<str>,
+    pub owner: Guild,
+    /// Time left on the attack cooldown, zero if the territory can be attacked
+    pub cooldown: SignedDuration,
+    /// How long the territory has been held if known
+    pub held: Option<SignedDuration>,
+    /// Tier estimated from the time held
+    pub tier: WynnTier,
+    /// Number of territories of the claim connected to this territory
+    pub touching: usize,
+}
+
+impl FrontlineTerr {
+    /// Whether the attack cooldown of the territory has expired
+    #[inline]
+    pub fn attackable(&self) -> bool {
+        self.cooldown <= SignedDuration::ZERO
+    }
+}
+
+/// Estimate the tier of a territory from how long it has been held
+///
+/// Territories held for an unknown time are assumed to be the strongest.
+pub fn estimated_tier(state: &TerrState, now: Timestamp) -> WynnTier {
+    state.acquired.map_or(WynnTier::VeryHigh, |a| {
+        WynnTier::from_time_held(now.duration_since(a))
+    })
+}
+
+/// Time left on the attack cooldown of a territory, zero if it can be attacked
+pub fn cooldown_left(state: &TerrState, now: Timestamp) -> SignedDuration {
+    state.acquired.map_or(SignedDuration::ZERO, |a| {
+        (ATTACK_COOLDOWN - now.duration_since(a)).max(SignedDuration::ZERO)
+    })
+}
+
+/// Find the frontline of the claim of the guild with the given prefix ordered from the best attack target
+pub fn frontline(
+    terrs: &BTreeMap<Arc<str>, Territory>,
+    state: &BTreeMap<Arc<str>, TerrState>,
+    prefix: &str,
+    now: Timestamp,
+) -> Vec<FrontlineTerr> {
+    let graph = TerrGraph::new(terrs);
+    let owned = |n: &str| state.get(n).is_some_and(|s| &*s.guild.prefix == prefix);
+
+    let mut front: Vec<_> = graph
+        .names()
+        .iter()
+        .filter(|n| !owned(n))
+        .filter_map(|name| {
+            let touching = graph.connections(name).iter().filter(|c| owned(c)).count();
+
+            if touching == 0 {
+                return None;
+            }
+
+            let s = state.get(name).cloned().unwrap_or_default();
+
+            Some(FrontlineTerr {
+                name: name.clone(),
+                cooldown: cooldown_left(&s, now),
+                held: s.acquired.map(|a| now.duration_since(a)),
+                tier: estimated_tier(&s, now),
+                owner: s.guild,
+                touching,
+            })
+        })
+        .collect();
+
+    front.sort_by_key(|t| {
+        (
+            !t.attackable(),
+            t.tier,
+            Reverse(t.touching),
+            t.held.unwrap_or(SignedDuration::MAX),
+        )
+    });
+
+    front
+}
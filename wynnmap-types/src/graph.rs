@@ -0,0 +1,329 @@
+//! Territory graph utilities
+//!
+//! This module contains an indexed representation of the territory connection graph along with the common graph
+//! algorithms used by the different map modes such as hop distances, paths, components and articulation points.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, VecDeque},
+    sync::Arc,
+};
+
+use crate::terr::{TerrState, Territory};
+
+/// The radius in connections within which territories count as externals of a territory
+pub const EXTERNALS_RADIUS: usize = 3;
+
+/// Indexed adjacency graph of territories
+///
+/// Connections are treated as undirected, so a connection listed by only one of the two territories still links both.
+/// Connections to territories which are not part of the graph are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TerrGraph {
+    /// Territory names ordered by their index
+    names: Vec<Arc<str>>,
+    /// Lookup from territory names to indices
+    index: BTreeMap<Arc<str>, usize>,
+    /// Sorted neighbour indices of each territory
+    adj: Vec<Vec<usize>>,
+}
+
+impl TerrGraph {
+    /// Build the graph from the territory list
+    pub fn new(terrs: &BTreeMap<Arc<str>, Territory>) -> Self {
+        Self::filtered(terrs, |_| true)
+    }
+
+    /// Build the graph from the territory list only including the territories accepted by the filter
+    pub fn filtered(
+        terrs: &BTreeMap<Arc<str>, Territory>,
+        mut filter: impl FnMut(&Arc<str>) -> bool,
+    ) -> Self {
+        let names: Vec<Arc<str>> = terrs.keys().filter(|n| filter(n)).cloned().collect();
+
+        let index: BTreeMap<Arc<str>, usize> = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.clone(), i))
+            .collect();
+
+        let mut adj = vec![BTreeSet::new(); names.len()];
+
+        for (i, name) in names.iter().enumerate() {
+            for conn in &terrs[name].connections {
+                if let Some(&j) = index.get(conn)
+                    && i != j
+                {
+                    adj[i].insert(j);
+                    adj[j].insert(i);
+                }
+            }
+        }
+
+        Self {
+            names,
+            index,
+            adj: adj.into_iter().map(|a| a.into_iter().collect()).collect(),
+        }
+    }
+
+    /// Build the graph of the territories owned by the guild with the given prefix
+    pub fn guild(
+        terrs: &BTreeMap<Arc<str>, Territory>,
+        state: &BTreeMap<Arc<str>, TerrState>,
+        prefix: &str,
+    ) -> Self {
+        Self::filtered(terrs, |n| {
+            state.get(n).is_some_and(|s| &*s.guild.prefix == prefix)
+        })
+    }
+
+    /// Number of territories in the graph
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Whether the graph has no territories
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Whether the given territory is a part of this graph
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    /// Get the index of a territory
+    #[inline]
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+
+    /// Get the name of the territory at the given index
+    #[inline]
+    pub fn name(&self, idx: usize) -> &Arc<str> {
+        &self.names[idx]
+    }
+
+    /// All territory names in index order
+    #[inline]
+    pub fn names(&self) -> &[Arc<str>] {
+        &self.names
+    }
+
+    /// Indices of the territories directly connected to the territory at the given index
+    #[inline]
+    pub fn neighbours(&self, idx: usize) -> &[usize] {
+        &self.adj[idx]
+    }
+
+    /// Names of the territories directly connected to the given territory
+    pub fn connections(&self, name: &str) -> BTreeSet<Arc<str>> {
+        self.index_of(name)
+            .map(|i| self.names_of(self.adj[i].iter().copied()))
+            .unwrap_or_default()
+    }
+
+    /// Breadth first search from the given index returning the hop distance and the previous territory on the path
+    /// for every reached territory. The search stops at `max_hops` if given.
+    fn bfs(&self, start: usize, max_hops: Option<usize>) -> Vec<Option<(usize, usize)>> {
+        let mut visited = vec![None; self.len()];
+        let mut queue = VecDeque::from([start]);
+
+        visited[start] = Some((0, start));
+
+        while let Some(cur) = queue.pop_front() {
+            let (dist, _) = visited[cur].expect("queued territories are always visited");
+
+            if max_hops.is_some_and(|m| dist >= m) {
+                continue;
+            }
+
+            for &next in &self.adj[cur] {
+                if visited[next].is_none() {
+                    visited[next] = Some((dist + 1, cur));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        visited
+    }
+
+    /// Hop distances from the given territory to every territory reachable from it
+    ///
+    /// The starting territory is included with a distance of 0.
+    pub fn distances(&self, from: &str) -> BTreeMap<Arc<str>, usize> {
+        self.distances_within(from, usize::MAX)
+    }
+
+    /// Hop distances from the given territory to every territory within `max_hops` connections
+    pub fn distances_within(&self, from: &str, max_hops: usize) -> BTreeMap<Arc<str>, usize> {
+        let Some(start) = self.index_of(from) else {
+            return BTreeMap::new();
+        };
+
+        self.bfs(start, Some(max_hops))
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, v)| v.map(|(d, _)| (self.names[i].clone(), d)))
+            .collect()
+    }
+
+    /// Find a shortest path between two territories
+    ///
+    /// The returned path includes both the start and the end territories. If no path exists `None` is returned.
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<Vec<Arc<str>>> {
+        let start = self.index_of(from)?;
+        let end = self.index_of(to)?;
+
+        let visited = self.bfs(start, None);
+
+        // walk the path backwards from the end
+        let mut path = vec![end];
+        let mut cur = end;
+
+        while cur != start {
+            let (_, prev) = visited[cur]?;
+            path.push(prev);
+            cur = prev;
+        }
+
+        path.reverse();
+
+        Some(path.into_iter().map(|i| self.names[i].clone()).collect())
+    }
+
+    /// Build the shortest path tree towards the given territory
+    ///
+    /// The returned map gives every territory which can reach `to` the neighbour which is one hop closer to it.
+    /// The target territory itself is not included.
+    pub fn next_hops(&self, to: &str) -> BTreeMap<Arc<str>, Arc<str>> {
+        let Some(root) = self.index_of(to) else {
+            return BTreeMap::new();
+        };
+
+        self.bfs(root, None)
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| *i != root)
+            .filter_map(|(i, v)| {
+                v.map(|(_, prev)| (self.names[i].clone(), self.names[prev].clone()))
+            })
+            .collect()
+    }
+
+    /// Find the territories within `radius` connections of the given territory, excluding the territory itself
+    pub fn externals(&self, name: &str, radius: usize) -> BTreeSet<Arc<str>> {
+        let mut exts: BTreeSet<_> = self.distances_within(name, radius).into_keys().collect();
+
+        exts.remove(name);
+
+        exts
+    }
+
+    /// Split the graph into its connected components
+    ///
+    /// Components are ordered from largest to smallest.
+    pub fn components(&self) -> Vec<BTreeSet<Arc<str>>> {
+        let mut component_of = vec![None; self.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.len() {
+            if component_of[start].is_some() {
+                continue;
+            }
+
+            let id = components.len();
+            let mut members = Vec::new();
+            let mut stack = vec![start];
+            component_of[start] = Some(id);
+
+            while let Some(cur) = stack.pop() {
+                members.push(cur);
+
+                for &next in &self.adj[cur] {
+                    if component_of[next].is_none() {
+                        component_of[next] = Some(id);
+                        stack.push(next);
+                    }
+                }
+            }
+
+            components.push(self.names_of(members));
+        }
+
+        components.sort_by_key(|c| std::cmp::Reverse(c.len()));
+
+        components
+    }
+
+    /// Find the articulation points, or "cut territories", of the graph
+    ///
+    /// An articulation point is a territory whose removal splits its component into multiple parts.
+    pub fn articulation_points(&self) -> BTreeSet<Arc<str>> {
+        let n = self.len();
+
+        let mut disc = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut is_cut = vec![false; n];
+        let mut time = 0;
+
+        for root in 0..n {
+            if disc[root] != usize::MAX {
+                continue;
+            }
+
+            disc[root] = time;
+            low[root] = time;
+            time += 1;
+
+            let mut root_children = 0;
+            // iterative dfs holding (node, parent, next neighbour position)
+            let mut stack = vec![(root, usize::MAX, 0)];
+
+            while let Some(&mut (cur, parent, ref mut pos)) = stack.last_mut() {
+                if let Some(&next) = self.adj[cur].get(*pos) {
+                    *pos += 1;
+
+                    if disc[next] == usize::MAX {
+                        disc[next] = time;
+                        low[next] = time;
+                        time += 1;
+
+                        if cur == root {
+                            root_children += 1;
+                        }
+
+                        stack.push((next, cur, 0));
+                    } else if next != parent {
+                        low[cur] = low[cur].min(disc[next]);
+                    }
+                } else {
+                    stack.pop();
+
+                    if parent != usize::MAX {
+                        low[parent] = low[parent].min(low[cur]);
+
+                        if parent != root && low[cur] >= disc[parent] {
+                            is_cut[parent] = true;
+                        }
+                    }
+                }
+            }
+
+            if root_children > 1 {
+                is_cut[root] = true;
+            }
+        }
+
+        self.names_of((0..n).filter(|&i| is_cut[i]))
+    }
+
+    /// Convert the given indices into a set of names
+    fn names_of(&self, idx: impl IntoIterator<Item = usize>) -> BTreeSet<Arc<str>> {
+        idx.into_iter().map(|i| self.names[i].clone()).collect()
+    }
+}
@@ -2,13 +2,21 @@ use serde::{Deserialize, Serialize};
 
 pub mod ws;
 
+pub mod attack;
+pub mod claim;
 pub mod encoding;
+pub mod frontline;
 pub mod gather;
+pub mod graph;
 pub mod guild;
 pub mod maptile;
+pub mod plan;
 pub mod resources;
+pub mod routing;
+pub mod session;
 pub mod terr;
 pub mod tier;
+pub mod tower;
 
 /// A rectangular region in the minecraft world
 #[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
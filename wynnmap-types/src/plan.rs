@@ -0,0 +1,29 @@
+//! Plan storage type definitions
+//!
+//! Planning mode plans can be stored on the server to share them with short links instead of long share urls.
+
+use std::sync::Arc;
+
+use jiff::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of the id of a stored plan
+pub const MAX_PLAN_ID_LEN: usize = 43;
+
+/// Response to storing a plan on the server
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct StoredPlan {
+    /// Id used to load the plan
+    pub id: Arc<str>,
+    /// When the plan will be deleted if ever
+    pub expires: Option<Timestamp>,
+}
+
+/// Check that a plan id only contains url safe base64 characters
+pub fn is_valid_plan_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_PLAN_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
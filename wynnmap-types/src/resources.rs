@@ -1,3 +1,8 @@
+use std::{
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone, Copy)]
@@ -92,6 +97,35 @@ impl BaseResGen {
     }
 }
 
+impl Add for BaseResGen {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            emerald: self.emerald + rhs.emerald,
+            ore: self.ore + rhs.ore,
+            crop: self.crop + rhs.crop,
+            fish: self.fish + rhs.fish,
+            wood: self.wood + rhs.wood,
+        }
+    }
+}
+
+impl AddAssign for BaseResGen {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sum for BaseResGen {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone, Default)]
 pub struct Resources {
     pub emerald: ResourceValues,
@@ -125,3 +159,38 @@ impl From<(i32, i32, i32)> for ResourceValues {
         }
     }
 }
+
+impl Add for ResourceValues {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            generation: self.generation + rhs.generation,
+            stored: self.stored + rhs.stored,
+            limit: self.limit + rhs.limit,
+        }
+    }
+}
+
+impl Add for Resources {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            emerald: self.emerald + rhs.emerald,
+            ore: self.ore + rhs.ore,
+            crop: self.crop + rhs.crop,
+            fish: self.fish + rhs.fish,
+            wood: self.wood + rhs.wood,
+        }
+    }
+}
+
+impl Sum for Resources {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
@@ -0,0 +1,115 @@
+//! Guild trade routing
+//!
+//! Resources produced by a guilds territories are routed to the guild hq through the territories owned by the guild.
+//! This module calculates these routes and the resulting resource flow through each territory.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use crate::{
+    graph::TerrGraph,
+    resources::BaseResGen,
+    terr::{TerrState, Territory},
+};
+
+/// The trade routes of a single guild claim
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TradeRoutes {
+    /// Name of the hq territory which all resources are routed to
+    pub hq: Arc<str>,
+    /// The next territory on the route towards the hq for each territory connected to the hq
+    pub next_hop: BTreeMap<Arc<str>, Arc<str>>,
+    /// Number of hops from each connected territory to the hq
+    pub distance: BTreeMap<Arc<str>, usize>,
+    /// Total base resource generation flowing through each connected territory
+    ///
+    /// This includes the resources produced by the territory itself so the hq contains the total of the whole claim.
+    pub flow: BTreeMap<Arc<str>, BaseResGen>,
+    /// Territories of the claim which have no route to the hq
+    pub cut_off: BTreeSet<Arc<str>>,
+}
+
+impl TradeRoutes {
+    /// Calculate the trade routes of the guild with the given prefix
+    ///
+    /// Returns `None` if the guild doesn't have a hq.
+    pub fn for_guild(
+        terrs: &BTreeMap<Arc<str>, Territory>,
+        state: &BTreeMap<Arc<str>, TerrState>,
+        prefix: &str,
+    ) -> Option<Self> {
+        let hq = state
+            .iter()
+            .find(|(_, s)| s.hq && &*s.guild.prefix == prefix)
+            .map(|(n, _)| n.clone())?;
+
+        Some(Self::with_hq(
+            terrs,
+            &TerrGraph::guild(terrs, state, prefix),
+            hq,
+        ))
+    }
+
+    /// Calculate the trade routes of a claim given as a graph with the given hq
+    pub fn with_hq(terrs: &BTreeMap<Arc<str>, Territory>, claim: &TerrGraph, hq: Arc<str>) -> Self {
+        let next_hop = claim.next_hops(&hq);
+        let distance = claim.distances(&hq);
+
+        let cut_off = claim
+            .names()
+            .iter()
+            .filter(|n| !distance.contains_key(*n))
+            .cloned()
+            .collect();
+
+        // accumulate the flows starting from the territories furthest away from the hq
+        let mut order: Vec<_> = distance.iter().collect();
+        order.sort_by_key(|(_, d)| std::cmp::Reverse(**d));
+
+        let mut flow: BTreeMap<Arc<str>, BaseResGen> = BTreeMap::new();
+
+        for (name, _) in order {
+            let own = terrs.get(name).map(|t| t.generates).unwrap_or_default();
+            let total = *flow.entry(name.clone()).or_default() + own;
+
+            flow.insert(name.clone(), total);
+
+            if let Some(next) = next_hop.get(name) {
+                *flow.entry(next.clone()).or_default() += total;
+            }
+        }
+
+        Self {
+            hq,
+            next_hop,
+            distance,
+            flow,
+            cut_off,
+        }
+    }
+
+    /// Get the route from the given territory to the hq
+    ///
+    /// The route includes both the territory and the hq. Returns `None` if the territory is not connected to the hq.
+    pub fn route(&self, from: &Arc<str>) -> Option<Vec<Arc<str>>> {
+        if !self.distance.contains_key(from) {
+            return None;
+        }
+
+        let mut route = vec![from.clone()];
+
+        while let Some(next) = self.next_hop.get(route.last()?) {
+            route.push(next.clone());
+        }
+
+        Some(route)
+    }
+
+    /// Whether the given territory is a part of this claim
+    #[inline]
+    pub fn contains(&self, name: &str) -> bool {
+        self.distance.contains_key(name) || self.cut_off.contains(name)
+    }
+}
@@ -0,0 +1,361 @@
+//! Collaborative planning session type definitions
+//!
+//! Participants of a session share a single plan. Every edit is sent to the server as an operation which the server
+//! applies to its copy of the plan and relays to every participant, including the sender, in the order it received
+//! them. Operations only ever set values so applying them in the server's order makes every participant end up with the
+//! same plan even when edits conflict: the edit which reached the server last wins.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{guild::Guild, tower::TowerUpgrades};
+
+/// Id of the guild which owns every territory without an owner
+pub const NOBODY_ID: u64 = 0;
+
+/// Maximum number of participants in a single session
+pub const MAX_PARTICIPANTS: usize = 32;
+
+/// Maximum number of guilds in a session plan
+pub const MAX_GUILDS: usize = 256;
+
+/// Maximum number of owned territories in a session plan
+pub const MAX_TERRITORIES: usize = 1024;
+
+/// Maximum length of a territory note in a session plan
+pub const MAX_NOTE_LEN: usize = 1024;
+
+/// Maximum length of names in a session, such as territory, guild and participant names
+pub const MAX_NAME_LEN: usize = 64;
+
+/// Whether a name is short enough to be used in a session
+#[inline]
+pub fn is_valid_name(name: &str) -> bool {
+    name.len() <= MAX_NAME_LEN
+}
+
+fn is_valid_guild(guild: &Guild) -> bool {
+    is_valid_name(&guild.name)
+        && is_valid_name(&guild.prefix)
+        && guild.color.as_deref().is_none_or(is_valid_name)
+}
+
+/// The plan shared by the participants of a session
+///
+/// Guilds are identified by ids since participants can't refer to each others guilds otherwise. The guild with id
+/// [`NOBODY_ID`] is always present.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct SessionPlan {
+    /// Guilds of the plan in display order
+    pub guilds: Vec<(u64, Guild)>,
+    /// Owner guild id of each owned territory
+    pub owners: BTreeMap<Arc<str>, u64>,
+    /// Names of the territories which are guild hqs
+    pub hqs: BTreeSet<Arc<str>>,
+    /// Notes written on territories
+    pub notes: BTreeMap<Arc<str>, String>,
+    /// Tower upgrades of territories without the default upgrades
+    pub upgrades: BTreeMap<Arc<str>, TowerUpgrades>,
+}
+
+impl Default for SessionPlan {
+    fn default() -> Self {
+        Self {
+            guilds: vec![(NOBODY_ID, Guild::default())],
+            owners: BTreeMap::new(),
+            hqs: BTreeSet::new(),
+            notes: BTreeMap::new(),
+            upgrades: BTreeMap::new(),
+        }
+    }
+}
+
+/// An edit to a session plan
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SessionOp {
+    /// Add a guild or replace the values of an existing guild
+    #[serde(rename = "g")]
+    Guild(u64, Guild),
+    /// Remove a guild giving its territories to nobody
+    #[serde(rename = "r")]
+    RemoveGuild(u64),
+    /// Give territories to a guild
+    #[serde(rename = "a")]
+    Assign(Vec<Arc<str>>, u64),
+    /// Set whether a territory is the hq of its owner
+    #[serde(rename = "h")]
+    SetHq(Arc<str>, bool),
+    /// Set or remove the note of a territory
+    #[serde(rename = "n")]
+    SetNote(Arc<str>, Option<String>),
+    /// Set or reset the tower upgrades of a territory
+    #[serde(rename = "u")]
+    SetUpgrades(Arc<str>, Option<TowerUpgrades>),
+}
+
+impl SessionPlan {
+    fn has_guild(&self, id: u64) -> bool {
+        self.guilds.iter().any(|(g, _)| *g == id)
+    }
+
+    /// Whether the plan stays within the size limits of a session
+    pub fn is_within_limits(&self) -> bool {
+        self.guilds.len() <= MAX_GUILDS
+            && self.guilds.iter().all(|(_, g)| is_valid_guild(g))
+            && self.owners.len() <= MAX_TERRITORIES
+            && self.owners.keys().all(|t| is_valid_name(t))
+            && self.hqs.len() <= MAX_TERRITORIES
+            && self.hqs.iter().all(|t| is_valid_name(t))
+            && self.notes.len() <= MAX_TERRITORIES
+            && self
+                .notes
+                .iter()
+                .all(|(t, n)| is_valid_name(t) && n.len() <= MAX_NOTE_LEN)
+            && self.upgrades.len() <= MAX_TERRITORIES
+            && self.upgrades.keys().all(|t| is_valid_name(t))
+    }
+
+    /// Apply an operation returning whether it was valid
+    ///
+    /// Invalid operations, such as assigning territories to a guild which has been removed or operations which would
+    /// make the plan exceed the size limits, are ignored.
+    pub fn apply(&mut self, op: &SessionOp) -> bool {
+        match op {
+            SessionOp::Guild(id, guild) => {
+                if !is_valid_guild(guild) {
+                    return false;
+                }
+
+                let full = self.guilds.len() >= MAX_GUILDS;
+
+                match self.guilds.iter_mut().find(|(g, _)| g == id) {
+                    Some((_, g)) => *g = guild.clone(),
+                    None if !full => {
+                        self.guilds.push((*id, guild.clone()));
+                    }
+                    None => return false,
+                }
+            }
+            SessionOp::RemoveGuild(id) => {
+                if *id == NOBODY_ID || !self.has_guild(*id) {
+                    return false;
+                }
+
+                self.guilds.retain(|(g, _)| g != id);
+
+                let removed: BTreeSet<_> = self
+                    .owners
+                    .iter()
+                    .filter(|(_, g)| *g == id)
+                    .map(|(t, _)| t.clone())
+                    .collect();
+
+                self.owners.retain(|t, _| !removed.contains(t));
+                self.hqs.retain(|t| !removed.contains(t));
+            }
+            SessionOp::Assign(terrs, id) => {
+                if !self.has_guild(*id) || !terrs.iter().all(|t| is_valid_name(t)) {
+                    return false;
+                }
+
+                let added = terrs
+                    .iter()
+                    .collect::<BTreeSet<_>>()
+                    .into_iter()
+                    .filter(|t| !self.owners.contains_key(*t))
+                    .count();
+                if *id != NOBODY_ID && self.owners.len() + added > MAX_TERRITORIES {
+                    return false;
+                }
+
+                for terr in terrs {
+                    let old = self.owners.get(terr).copied().unwrap_or(NOBODY_ID);
+
+                    if old == *id {
+                        continue;
+                    }
+
+                    // a territory stops being an hq when it changes owner
+                    self.hqs.remove(terr);
+
+                    if *id == NOBODY_ID {
+                        self.owners.remove(terr);
+                    } else {
+                        self.owners.insert(terr.clone(), *id);
+                    }
+                }
+            }
+            SessionOp::SetHq(terr, false) => {
+                self.hqs.remove(terr);
+            }
+            SessionOp::SetHq(terr, true) => {
+                let Some(owner) = self.owners.get(terr).copied() else {
+                    return false;
+                };
+
+                // a guild may only have a single hq
+                self.hqs
+                    .retain(|t| self.owners.get(t).is_none_or(|g| *g != owner));
+                self.hqs.insert(terr.clone());
+            }
+            SessionOp::SetNote(terr, None) => {
+                self.notes.remove(terr);
+            }
+            SessionOp::SetNote(terr, Some(note)) => {
+                if !is_valid_name(terr)
+                    || note.len() > MAX_NOTE_LEN
+                    || (!self.notes.contains_key(terr) && self.notes.len() >= MAX_TERRITORIES)
+                {
+                    return false;
+                }
+
+                self.notes.insert(terr.clone(), note.clone());
+            }
+            SessionOp::SetUpgrades(terr, None) => {
+                self.upgrades.remove(terr);
+            }
+            SessionOp::SetUpgrades(terr, Some(upgrades)) => {
+                if !is_valid_name(terr)
+                    || (!self.upgrades.contains_key(terr) && self.upgrades.len() >= MAX_TERRITORIES)
+                {
+                    return false;
+                }
+
+                self.upgrades.insert(terr.clone(), *upgrades);
+            }
+        }
+
+        true
+    }
+
+    /// Find the operations which turn this plan into the other plan
+    pub fn diff(&self, other: &Self) -> Vec<SessionOp> {
+        let mut ops = Vec::new();
+
+        for (id, guild) in &other.guilds {
+            if !self.guilds.iter().any(|(g, v)| g == id && v == guild) {
+                ops.push(SessionOp::Guild(*id, guild.clone()));
+            }
+        }
+
+        // group the changed territories by their new owner
+        let mut assigns: BTreeMap<u64, Vec<Arc<str>>> = BTreeMap::new();
+        let terrs: BTreeSet<_> = self.owners.keys().chain(other.owners.keys()).collect();
+
+        for terr in terrs {
+            let old = self.owners.get(terr).copied().unwrap_or(NOBODY_ID);
+            let new = other.owners.get(terr).copied().unwrap_or(NOBODY_ID);
+
+            if old != new {
+                assigns.entry(new).or_default().push(terr.clone());
+            }
+        }
+
+        ops.extend(assigns.into_iter().map(|(id, t)| SessionOp::Assign(t, id)));
+
+        ops.extend(
+            self.hqs
+                .difference(&other.hqs)
+                .map(|t| SessionOp::SetHq(t.clone(), false)),
+        );
+        ops.extend(
+            other
+                .hqs
+                .difference(&self.hqs)
+                .map(|t| SessionOp::SetHq(t.clone(), true)),
+        );
+
+        ops.extend(
+            changed(&self.notes, &other.notes).map(|(t, n)| SessionOp::SetNote(t, n.cloned())),
+        );
+        ops.extend(
+            changed(&self.upgrades, &other.upgrades)
+                .map(|(t, u)| SessionOp::SetUpgrades(t, u.copied())),
+        );
+
+        for (id, _) in &self.guilds {
+            if !other.has_guild(*id) {
+                ops.push(SessionOp::RemoveGuild(*id));
+            }
+        }
+
+        ops
+    }
+}
+
+/// The territories whose value differs between the maps together with their value in the new map
+fn changed<'a, T: PartialEq>(
+    old: &'a BTreeMap<Arc<str>, T>,
+    new: &'a BTreeMap<Arc<str>, T>,
+) -> impl Iterator<Item = (Arc<str>, Option<&'a T>)> {
+    let terrs: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+
+    terrs.into_iter().filter_map(|t| {
+        let value = new.get(t);
+        (old.get(t) != value).then(|| (t.clone(), value))
+    })
+}
+
+/// What a participant is currently looking at
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct Presence {
+    /// The territory selected by the participant
+    pub selected: Option<Arc<str>>,
+    /// Territories selected with the selection tools
+    pub selection: BTreeSet<Arc<str>>,
+}
+
+impl Presence {
+    /// Whether the presence stays within the size limits of a session
+    pub fn is_within_limits(&self) -> bool {
+        self.selection.len() <= MAX_TERRITORIES
+            && self
+                .selected
+                .iter()
+                .chain(&self.selection)
+                .all(|t| is_valid_name(t))
+    }
+}
+
+/// A participant of a session
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
+pub struct Participant {
+    pub id: u32,
+    pub name: Arc<str>,
+    pub presence: Presence,
+}
+
+/// Messages sent by participants to the server
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SessionClientMessage {
+    /// First message of every connection
+    ///
+    /// The plan is used to start the session if nobody else is in it yet.
+    #[serde(rename = "j")]
+    Join(Arc<str>, SessionPlan),
+    #[serde(rename = "o")]
+    Op(SessionOp),
+    #[serde(rename = "p")]
+    Presence(Presence),
+}
+
+/// Messages sent by the server to participants
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SessionServerMessage {
+    /// Reply to joining with the id of the participant, the current plan and the other participants
+    #[serde(rename = "w")]
+    Welcome(u32, SessionPlan, Vec<Participant>),
+    /// An operation applied by the participant with the given id
+    #[serde(rename = "o")]
+    Op(u32, SessionOp),
+    #[serde(rename = "j")]
+    Joined(Participant),
+    #[serde(rename = "l")]
+    Left(u32),
+    #[serde(rename = "p")]
+    Presence(u32, Presence),
+}
@@ -8,6 +8,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     Region,
+    graph::{EXTERNALS_RADIUS, TerrGraph},
     guild::{CompactGuild, Guild},
     resources::{BaseResGen, Resources},
     tier::{CompactTier, WynnTier},
@@ -33,25 +34,7 @@ pub fn find_externals(
     name: &Arc<str>,
     territories: &BTreeMap<Arc<str>, Territory>,
 ) -> BTreeSet<Arc<str>> {
-    let mut externals = BTreeSet::new();
-
-    externals.insert(name.clone());
-
-    for _ in 0..3 {
-        let exts = externals.clone();
-
-        for ext in exts {
-            if let Some(external) = territories.get(&ext) {
-                for territory in &external.connections {
-                    externals.insert(territory.clone());
-                }
-            }
-        }
-    }
-
-    externals.remove(name);
-
-    externals
+    TerrGraph::new(territories).externals(name, EXTERNALS_RADIUS)
 }
 
 #[derive(Serialize, Deserialize)]
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::tier::WynnTier;
+
+/// The maximum level of the damage, attack, health and defence upgrades
+pub const MAX_UPGRADE: u8 = 11;
+/// The maximum level of the aura and volley bonuses
+pub const MAX_BONUS: u8 = 3;
+
+/// Tower upgrade levels of a territory
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TowerUpgrades {
+    pub damage: u8,
+    pub attack: u8,
+    pub health: u8,
+    pub defence: u8,
+    pub aura: u8,
+    pub volley: u8,
+}
+
+impl TowerUpgrades {
+    /// Tower with every upgrade and bonus maxed out
+    pub const MAX: Self = Self {
+        damage: MAX_UPGRADE,
+        attack: MAX_UPGRADE,
+        health: MAX_UPGRADE,
+        defence: MAX_UPGRADE,
+        aura: MAX_BONUS,
+        volley: MAX_BONUS,
+    };
+
+    /// Whether the tower has no upgrades
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Calculate the defence number used to determine the defence tier of the tower
+    #[inline]
+    pub const fn def_num(&self) -> i32 {
+        let mut x = self.damage as i32
+            + self.attack as i32
+            + self.health as i32
+            + self.defence as i32
+            + self.aura as i32
+            + self.volley as i32;
+
+        if self.aura == 0 {
+            x -= 5;
+        }
+        if self.volley == 0 {
+            x -= 3;
+        }
+
+        x
+    }
+
+    /// The defence tier of the tower
+    #[inline]
+    pub const fn tier(&self) -> WynnTier {
+        WynnTier::from_defnum(self.def_num())
+    }
+}
+
+/// Multiplier of the damage and health of a tower from its connections and, for hqs, its externals
+///
+/// Only connections and externals owned by the same guild count.
+#[inline]
+pub fn stat_multiplier(hq: bool, conns: i32, externals: i32) -> f64 {
+    let conn_mult = 0.3f64.mul_add(f64::from(conns), 1.0);
+
+    if hq {
+        0.25f64.mul_add(f64::from(externals), 1.5) * conn_mult
+    } else {
+        conn_mult
+    }
+}